@@ -0,0 +1,663 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
+ **/
+use crate::fingerprint::function_fingerprint;
+use crate::selector::{Selector, SelectorCollector};
+use std::collections::{HashMap, HashSet};
+use swc_core::common::{
+    errors::SourceMapperDyn, BytePos, Globals, Mark, Span, SyntaxContext, GLOBALS,
+};
+use swc_core::ecma::{
+    ast::{
+        ArrowExpr, AssignExpr, AssignOp, AssignTarget, BlockStmt, Class, ClassDecl, ClassMember,
+        ClassMethod, Constructor, Decl, DefaultDecl, ExportDecl, ExportDefaultDecl,
+        ExportDefaultExpr, ExportSpecifier, Expr, FnDecl, FnExpr, Function, GetterProp, Id, Ident,
+        KeyValueProp, Lit, MemberExpr, MemberProp, MethodProp, ModuleExportName, NamedExport, Pat,
+        Program, Prop, PropName, PropOrSpread, SetterProp, SimpleAssignTarget, TsExportAssignment,
+        VarDeclarator,
+    },
+    atoms::Atom,
+    transforms::base::resolver,
+    visit::{Visit, VisitMut, VisitMutWith, VisitWith},
+};
+
+/// The maximum number of passes made over a program while propagating export names through
+/// local aliases. Each pass follows one more level of aliasing, so this only needs to be as deep
+/// as the longest alias chain we care about.
+const MAX_EXPORT_PASSES: usize = 8;
+
+/// Information about a single function in the program, gathered before any instrumentation is
+/// applied.
+#[derive(Debug, Default)]
+pub(crate) struct FunctionInfo {
+    pub(crate) span: Span,
+    /// The name the function is declared or assigned with, if any.
+    pub(crate) name: Option<String>,
+    /// Every path through which this function is reachable from the module's exports, such as
+    /// `fetch` or `Client.prototype.query`.
+    pub(crate) export_paths: Vec<String>,
+    /// The names of the named functions this one is nested in, outermost first. Anonymous
+    /// functions are skipped.
+    pub(crate) enclosing: Vec<String>,
+    /// Whether this function isn't nested in any other function, named or not.
+    pub(crate) top_level: bool,
+    /// Whether the binding this function is declared or assigned to shadows a binding of the
+    /// same name in an enclosing scope.
+    pub(crate) shadows: bool,
+    /// The 1-based line and column the function starts at, if a source map was available.
+    pub(crate) position: Option<(usize, usize)>,
+    /// The structural fingerprint of the function, if fingerprints were asked for. Class
+    /// constructors don't have one.
+    pub(crate) fingerprint: Option<String>,
+    /// The text of every selector that matches this function.
+    pub(crate) selectors: Vec<String>,
+}
+
+/// What to gather about functions besides their scopes and exports, which is only done when
+/// some instrumentation needs it.
+#[derive(Default)]
+pub(crate) struct AnalysisOptions<'a> {
+    /// Used to record the line and column each function starts at.
+    pub(crate) source_map: Option<&'a SourceMapperDyn>,
+    pub(crate) fingerprints: bool,
+    pub(crate) selectors: Vec<Selector>,
+}
+
+/// The result of analyzing a whole program ahead of instrumentation. Functions are keyed by the
+/// start of their span, which is left untouched when tracing code is inserted into them.
+#[derive(Debug, Default)]
+pub(crate) struct Analysis {
+    functions: HashMap<BytePos, FunctionInfo>,
+}
+
+impl Analysis {
+    /// Analyzes a program. Scope resolution is run on the given copy of the program, using its
+    /// own [`Globals`], so the marks applied here never leak into the program being instrumented.
+    pub(crate) fn new(mut program: Program, options: &AnalysisOptions) -> Self {
+        GLOBALS.set(&Globals::new(), || {
+            program.visit_mut_with(&mut ClearSyntaxContexts);
+            let unresolved_mark = Mark::new();
+            let top_level_mark = Mark::new();
+            program.visit_mut_with(&mut resolver(unresolved_mark, top_level_mark, true));
+
+            let unresolved = SyntaxContext::empty().apply_mark(unresolved_mark);
+
+            let mut bindings = BindingCollector {
+                unresolved,
+                marks: HashMap::new(),
+            };
+            program.visit_with(&mut bindings);
+            let mut scopes = ScopeCollector {
+                unresolved,
+                bindings: bindings.marks,
+                scopes: vec![top_level_mark],
+                enclosing: vec![],
+                depth: 0,
+                source_map: options.source_map,
+                fingerprints: options.fingerprints,
+                functions: HashMap::new(),
+            };
+            program.visit_with(&mut scopes);
+
+            let mut exports = ExportCollector {
+                unresolved,
+                ..Default::default()
+            };
+            for _ in 0..MAX_EXPORT_PASSES {
+                exports.changed = false;
+                program.visit_with(&mut exports);
+                if !exports.changed {
+                    break;
+                }
+            }
+
+            let mut selectors = SelectorCollector {
+                selectors: &options.selectors,
+                matches: HashMap::new(),
+            };
+            selectors.collect(&program);
+
+            let mut analysis = Analysis {
+                functions: scopes.functions,
+            };
+            for (pos, paths) in exports.functions {
+                let mut export_paths: Vec<String> = paths.into_iter().collect();
+                export_paths.sort();
+                analysis.functions.entry(pos).or_default().export_paths = export_paths;
+            }
+            for (pos, selectors) in selectors.matches {
+                analysis.functions.entry(pos).or_default().selectors = selectors;
+            }
+            analysis
+        })
+    }
+
+    /// Looks up a function, or a class constructor, by its span.
+    pub(crate) fn function(&self, span: Span) -> Option<&FunctionInfo> {
+        if span.is_dummy() {
+            return None;
+        }
+        self.functions.get(&span.lo)
+    }
+
+    pub(crate) fn functions(&self) -> impl Iterator<Item = &FunctionInfo> {
+        self.functions.values()
+    }
+}
+
+/// Resets every [`SyntaxContext`] so the resolver treats the program as freshly parsed, even if
+/// a resolver has already been run on it by whatever is hosting us.
+struct ClearSyntaxContexts;
+
+impl VisitMut for ClearSyntaxContexts {
+    fn visit_mut_syntax_context(&mut self, ctxt: &mut SyntaxContext) {
+        *ctxt = SyntaxContext::empty();
+    }
+}
+
+/// Collects the scope marks of every resolved binding, by name.
+struct BindingCollector {
+    unresolved: SyntaxContext,
+    marks: HashMap<Atom, HashSet<Mark>>,
+}
+
+impl Visit for BindingCollector {
+    fn visit_ident(&mut self, ident: &Ident) {
+        if ident.ctxt != self.unresolved && ident.ctxt != SyntaxContext::empty() {
+            self.marks
+                .entry(ident.sym.clone())
+                .or_default()
+                .insert(ident.ctxt.outer());
+        }
+    }
+}
+
+/// Records where each function sits relative to the functions and scopes around it.
+struct ScopeCollector<'a> {
+    unresolved: SyntaxContext,
+    bindings: HashMap<Atom, HashSet<Mark>>,
+    scopes: Vec<Mark>,
+    enclosing: Vec<String>,
+    depth: usize,
+    source_map: Option<&'a SourceMapperDyn>,
+    fingerprints: bool,
+    functions: HashMap<BytePos, FunctionInfo>,
+}
+
+fn unwrap_parens(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(paren) => unwrap_parens(&paren.expr),
+        _ => expr,
+    }
+}
+
+impl ScopeCollector<'_> {
+    fn shadows(&self, binding: &Ident) -> bool {
+        if binding.ctxt == self.unresolved {
+            return false;
+        }
+        let mark = binding.ctxt.outer();
+        self.bindings.get(&binding.sym).is_some_and(|marks| {
+            self.scopes
+                .iter()
+                .any(|scope| *scope != mark && marks.contains(scope))
+        })
+    }
+
+    fn record(
+        &mut self,
+        span: Span,
+        name: Option<&String>,
+        binding: Option<&Ident>,
+        func: Option<&Function>,
+    ) {
+        if span.is_dummy() {
+            return;
+        }
+        let info = FunctionInfo {
+            span,
+            name: name.cloned(),
+            enclosing: self.enclosing.clone(),
+            top_level: self.depth == 0,
+            shadows: binding.is_some_and(|binding| self.shadows(binding)),
+            position: self.source_map.map(|source_map| {
+                let loc = source_map.lookup_char_pos(span.lo);
+                (loc.line, loc.col.0 + 1)
+            }),
+            fingerprint: func.filter(|_| self.fingerprints).map(function_fingerprint),
+            ..Default::default()
+        };
+        self.functions.insert(span.lo, info);
+    }
+
+    fn enter<F>(&mut self, name: Option<String>, ctxt: SyntaxContext, visit: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let named = name.is_some();
+        if let Some(name) = name {
+            self.enclosing.push(name);
+        }
+        self.scopes.push(ctxt.outer());
+        self.depth += 1;
+        visit(self);
+        self.depth -= 1;
+        self.scopes.pop();
+        if named {
+            self.enclosing.pop();
+        }
+    }
+
+    fn enter_function(&mut self, func: &Function, name: Option<String>, binding: Option<&Ident>) {
+        self.record(func.span, name.as_ref(), binding, Some(func));
+        self.enter(name, func.ctxt, |this| func.visit_children_with(this));
+    }
+
+    fn enter_arrow(&mut self, arrow: &ArrowExpr, name: Option<String>) {
+        self.enter(name, arrow.ctxt, |this| arrow.visit_children_with(this));
+    }
+
+    /// Visits a value that's given a name by its surroundings, such as the initializer of a
+    /// variable declaration. Returns false if the value isn't a function.
+    fn enter_named_value(&mut self, expr: &Expr, name: String, binding: Option<&Ident>) -> bool {
+        match unwrap_parens(expr) {
+            Expr::Fn(fn_expr) => {
+                let name = fn_expr.ident.as_ref().map_or(name, |i| i.sym.to_string());
+                self.enter_function(&fn_expr.function, Some(name), binding);
+                true
+            }
+            Expr::Arrow(arrow) => {
+                self.enter_arrow(arrow, Some(name));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Visit for ScopeCollector<'_> {
+    fn visit_block_stmt(&mut self, node: &BlockStmt) {
+        if node.ctxt == SyntaxContext::empty() {
+            node.visit_children_with(self);
+        } else {
+            self.scopes.push(node.ctxt.outer());
+            node.visit_children_with(self);
+            self.scopes.pop();
+        }
+    }
+
+    fn visit_function(&mut self, node: &Function) {
+        self.enter_function(node, None, None);
+    }
+
+    fn visit_arrow_expr(&mut self, node: &ArrowExpr) {
+        self.enter_arrow(node, None);
+    }
+
+    fn visit_fn_decl(&mut self, node: &FnDecl) {
+        self.enter_function(
+            &node.function,
+            Some(node.ident.sym.to_string()),
+            Some(&node.ident),
+        );
+    }
+
+    fn visit_fn_expr(&mut self, node: &FnExpr) {
+        let name = node.ident.as_ref().map(|ident| ident.sym.to_string());
+        self.enter_function(&node.function, name, None);
+    }
+
+    fn visit_var_declarator(&mut self, node: &VarDeclarator) {
+        if let (Pat::Ident(name), Some(init)) = (&node.name, &node.init) {
+            if self.enter_named_value(init, name.id.sym.to_string(), Some(&name.id)) {
+                return;
+            }
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, node: &AssignExpr) {
+        if let AssignTarget::Simple(target) = &node.left {
+            let named = match target {
+                SimpleAssignTarget::Ident(ident) => {
+                    self.enter_named_value(&node.right, ident.sym.to_string(), Some(&ident.id))
+                }
+                SimpleAssignTarget::Member(member) => member_prop_str(&member.prop)
+                    .is_some_and(|name| self.enter_named_value(&node.right, name, None)),
+                _ => false,
+            };
+            if named {
+                return;
+            }
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_key_value_prop(&mut self, node: &KeyValueProp) {
+        if let Some(name) = prop_name_str(&node.key) {
+            if self.enter_named_value(&node.value, name, None) {
+                return;
+            }
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_method_prop(&mut self, node: &MethodProp) {
+        self.enter_function(&node.function, prop_name_str(&node.key), None);
+    }
+
+    fn visit_class_method(&mut self, node: &ClassMethod) {
+        self.enter_function(&node.function, prop_name_str(&node.key), None);
+    }
+
+    fn visit_getter_prop(&mut self, node: &GetterProp) {
+        self.enter(prop_name_str(&node.key), SyntaxContext::empty(), |this| {
+            node.visit_children_with(this);
+        });
+    }
+
+    fn visit_setter_prop(&mut self, node: &SetterProp) {
+        self.enter(prop_name_str(&node.key), SyntaxContext::empty(), |this| {
+            node.visit_children_with(this);
+        });
+    }
+
+    fn visit_constructor(&mut self, node: &Constructor) {
+        let name = "constructor".to_string();
+        self.record(node.span, Some(&name), None, None);
+        self.enter(Some(name), node.ctxt, |this| {
+            node.visit_children_with(this);
+        });
+    }
+}
+
+/// Collects the export paths of functions. It's run repeatedly until nothing new is found, since
+/// a binding may be exported (e.g. `exports.Undici2 = Undici`) after it has been declared.
+#[derive(Default)]
+struct ExportCollector {
+    unresolved: SyntaxContext,
+    bindings: HashMap<Id, HashSet<String>>,
+    functions: HashMap<BytePos, HashSet<String>>,
+    changed: bool,
+}
+
+fn join_path(base: &str, segment: &str) -> String {
+    if base.is_empty() {
+        segment.to_string()
+    } else {
+        format!("{base}.{segment}")
+    }
+}
+
+pub(crate) fn prop_name_str(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(str_lit) => Some(str_lit.value.to_string()),
+        _ => None,
+    }
+}
+
+fn member_prop_str(prop: &MemberProp) -> Option<String> {
+    match prop {
+        MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+        MemberProp::Computed(computed) => match computed.expr.as_lit() {
+            Some(Lit::Str(str_lit)) => Some(str_lit.value.to_string()),
+            _ => None,
+        },
+        MemberProp::PrivateName(_) => None,
+    }
+}
+
+/// Splits a member expression like `module.exports.Client.prototype` into its root identifier
+/// and the property names that follow it.
+pub(crate) fn member_segments(expr: &Expr) -> Option<(&Ident, Vec<String>)> {
+    match expr {
+        Expr::Ident(ident) => Some((ident, vec![])),
+        Expr::Paren(paren) => member_segments(&paren.expr),
+        Expr::Member(member) => member_expr_segments(member),
+        _ => None,
+    }
+}
+
+pub(crate) fn member_expr_segments(member: &MemberExpr) -> Option<(&Ident, Vec<String>)> {
+    let (root, mut segments) = member_segments(&member.obj)?;
+    segments.push(member_prop_str(&member.prop)?);
+    Some((root, segments))
+}
+
+impl ExportCollector {
+    fn is_global(&self, ident: &Ident, name: &str) -> bool {
+        ident.sym == name && ident.ctxt == self.unresolved
+    }
+
+    fn binding_paths(&self, ident: &Ident) -> Vec<String> {
+        self.bindings
+            .get(&ident.to_id())
+            .map(|paths| paths.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn add_binding(&mut self, ident: &Ident, path: String) {
+        if self.bindings.entry(ident.to_id()).or_default().insert(path) {
+            self.changed = true;
+        }
+    }
+
+    fn add_function(&mut self, func: &Function, path: String) {
+        if func.span.is_dummy() {
+            return;
+        }
+        if self.functions.entry(func.span.lo).or_default().insert(path) {
+            self.changed = true;
+        }
+    }
+
+    /// The export paths that an assignment to `target` makes its value reachable through. The
+    /// empty string stands for `module.exports` itself.
+    fn target_paths(&self, target: &MemberExpr) -> Vec<String> {
+        let Some((root, segments)) = member_expr_segments(target) else {
+            return vec![];
+        };
+        if self.is_global(root, "exports") {
+            vec![segments.join(".")]
+        } else if self.is_global(root, "module") {
+            match segments.split_first() {
+                Some((first, rest)) if first == "exports" => vec![rest.join(".")],
+                _ => vec![],
+            }
+        } else {
+            self.binding_paths(root)
+                .iter()
+                .map(|base| segments.iter().fold(base.clone(), |p, s| join_path(&p, s)))
+                .collect()
+        }
+    }
+
+    fn register_value(&mut self, expr: &Expr, paths: &[String]) {
+        if paths.is_empty() {
+            return;
+        }
+        if let Expr::Object(object) = expr {
+            for prop in &object.props {
+                let PropOrSpread::Prop(prop) = prop else {
+                    continue;
+                };
+                match prop.as_ref() {
+                    Prop::Shorthand(ident) => {
+                        for path in paths {
+                            self.add_binding(ident, join_path(path, &ident.sym));
+                        }
+                    }
+                    Prop::KeyValue(kv) => {
+                        if let Some(key) = prop_name_str(&kv.key) {
+                            let paths: Vec<String> =
+                                paths.iter().map(|p| join_path(p, &key)).collect();
+                            self.register_value(&kv.value, &paths);
+                        }
+                    }
+                    Prop::Method(method) => {
+                        if let Some(key) = prop_name_str(&method.key) {
+                            for path in paths {
+                                self.add_function(&method.function, join_path(path, &key));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            return;
+        }
+
+        // Anything other than an object assigned to `module.exports` is its default export.
+        let paths: Vec<String> = paths
+            .iter()
+            .map(|p| {
+                if p.is_empty() {
+                    "default".to_string()
+                } else {
+                    p.clone()
+                }
+            })
+            .collect();
+        match expr {
+            Expr::Paren(paren) => self.register_value(&paren.expr, &paths),
+            Expr::Ident(ident) => {
+                for path in paths {
+                    self.add_binding(ident, path);
+                }
+            }
+            Expr::Fn(fn_expr) => {
+                for path in paths {
+                    self.add_function(&fn_expr.function, path);
+                }
+            }
+            Expr::Class(class_expr) => self.register_class(&class_expr.class, &paths),
+            _ => {}
+        }
+    }
+
+    /// Instance methods are reachable both as `Class.method` and `Class.prototype.method`, while
+    /// static methods are only reachable as `Class.method`.
+    fn register_class(&mut self, class: &Class, paths: &[String]) {
+        for member in &class.body {
+            let ClassMember::Method(method) = member else {
+                continue;
+            };
+            let Some(key) = prop_name_str(&method.key) else {
+                continue;
+            };
+            for path in paths {
+                self.add_function(&method.function, join_path(path, &key));
+                if !method.is_static {
+                    let proto = join_path(path, "prototype");
+                    self.add_function(&method.function, join_path(&proto, &key));
+                }
+            }
+        }
+    }
+}
+
+impl Visit for ExportCollector {
+    fn visit_fn_decl(&mut self, node: &FnDecl) {
+        for path in self.binding_paths(&node.ident) {
+            self.add_function(&node.function, path);
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_class_decl(&mut self, node: &ClassDecl) {
+        let paths = self.binding_paths(&node.ident);
+        self.register_class(&node.class, &paths);
+        node.visit_children_with(self);
+    }
+
+    fn visit_var_declarator(&mut self, node: &VarDeclarator) {
+        if let (Pat::Ident(name), Some(init)) = (&node.name, &node.init) {
+            let paths = self.binding_paths(&name.id);
+            self.register_value(init, &paths);
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, node: &AssignExpr) {
+        if node.op == AssignOp::Assign {
+            if let AssignTarget::Simple(SimpleAssignTarget::Member(member)) = &node.left {
+                let paths = self.target_paths(member);
+                self.register_value(&node.right, &paths);
+            }
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_export_decl(&mut self, node: &ExportDecl) {
+        match &node.decl {
+            Decl::Fn(decl) => {
+                self.add_binding(&decl.ident, decl.ident.sym.to_string());
+            }
+            Decl::Class(decl) => {
+                self.add_binding(&decl.ident, decl.ident.sym.to_string());
+            }
+            Decl::Var(decl) => {
+                for declarator in &decl.decls {
+                    if let Pat::Ident(name) = &declarator.name {
+                        self.add_binding(&name.id, name.id.sym.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_named_export(&mut self, node: &NamedExport) {
+        // Re-exports from other modules don't refer to anything declared in this file.
+        if node.src.is_none() {
+            for specifier in &node.specifiers {
+                if let ExportSpecifier::Named(named) = specifier {
+                    let ModuleExportName::Ident(orig) = &named.orig else {
+                        continue;
+                    };
+                    let exported = match &named.exported {
+                        Some(ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+                        Some(ModuleExportName::Str(str_lit)) => str_lit.value.to_string(),
+                        None => orig.sym.to_string(),
+                    };
+                    self.add_binding(orig, exported);
+                }
+            }
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_export_default_decl(&mut self, node: &ExportDefaultDecl) {
+        let default = ["default".to_string()];
+        match &node.decl {
+            DefaultDecl::Fn(fn_expr) => {
+                if let Some(ident) = &fn_expr.ident {
+                    self.add_binding(ident, "default".to_string());
+                }
+                self.add_function(&fn_expr.function, "default".to_string());
+            }
+            DefaultDecl::Class(class_expr) => {
+                if let Some(ident) = &class_expr.ident {
+                    self.add_binding(ident, "default".to_string());
+                }
+                self.register_class(&class_expr.class, &default);
+            }
+            DefaultDecl::TsInterfaceDecl(_) => {}
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_export_default_expr(&mut self, node: &ExportDefaultExpr) {
+        self.register_value(&node.expr, &["default".to_string()]);
+        node.visit_children_with(self);
+    }
+
+    /// TypeScript's `export = value` compiles to `module.exports = value`.
+    fn visit_ts_export_assignment(&mut self, node: &TsExportAssignment) {
+        self.register_value(&node.expr, &[String::new()]);
+        node.visit_children_with(self);
+    }
+}
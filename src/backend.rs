@@ -0,0 +1,245 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
+ **/
+use swc_core::ecma::ast::{ModuleItem, Stmt, Str};
+use swc_core::quote;
+
+/// What the generated code traces functions with. Traced functions always publish to a channel
+/// created with `tracingChannel`, so every backend other than `diagnostics_channel` provides a
+/// small implementation of it, declared once in each instrumented file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TracingBackend {
+    /// `tracingChannel` from the module configured as [`Config::dc_module`].
+    ///
+    /// [`Config::dc_module`]: crate::Config::dc_module
+    #[default]
+    DiagnosticsChannel,
+    /// A registry of hooks on the global object, as `globalThis[Symbol.for(key)]`, which maps
+    /// the full names of channels, as in `"orchestrion:undici:fetch"`, to objects with any of
+    /// the `start`, `end`, `asyncStart`, `asyncEnd` and `error` functions of a `tracingChannel`
+    /// subscriber. Hooks are looked up on every call, so they can be added at any time, and
+    /// nothing else runs for functions without any. This needs nothing but the global object,
+    /// as in browsers and edge runtimes.
+    GlobalHook { key: String },
+    /// A span for every call, started with `startActiveSpan` on a tracer from the OpenTelemetry
+    /// API imported from `module`, which is named after the full name of the channel. The span is
+    /// published as `span` in the context. Constructors start their spans with `startSpan`,
+    /// since their bodies can't be moved into a callback.
+    OpenTelemetry { module: String, tracer_name: String },
+}
+
+impl TracingBackend {
+    /// A registry of hooks at `globalThis[Symbol.for("orchestrion")]`.
+    #[must_use]
+    pub fn global_hook() -> Self {
+        TracingBackend::GlobalHook {
+            key: "orchestrion".to_string(),
+        }
+    }
+
+    /// A tracer named `tracer_name` from `@opentelemetry/api`.
+    #[must_use]
+    pub fn open_telemetry(tracer_name: &str) -> Self {
+        TracingBackend::OpenTelemetry {
+            module: "@opentelemetry/api".to_string(),
+            tracer_name: tracer_name.to_string(),
+        }
+    }
+
+    /// The code declaring `tr_ch_apm_tracingChannel` at the start of an ES module.
+    pub(crate) fn module_prologue(&self, dc_module: &str) -> Vec<ModuleItem> {
+        let (mut import, module) = match self {
+            TracingBackend::DiagnosticsChannel => (
+                quote!(
+                    "import { tracingChannel as tr_ch_apm_tracingChannel } from 'dc';"
+                        as ModuleItem,
+                ),
+                dc_module,
+            ),
+            TracingBackend::GlobalHook { .. } => {
+                return self.shim().into_iter().map(ModuleItem::Stmt).collect()
+            }
+            TracingBackend::OpenTelemetry { module, .. } => (
+                quote!("import * as tr_ch_apm_otel from 'otel';" as ModuleItem),
+                module.as_str(),
+            ),
+        };
+        if let Some(decl) = import
+            .as_mut_module_decl()
+            .and_then(|decl| decl.as_mut_import())
+        {
+            *decl.src = Str::from(module);
+        }
+        std::iter::once(import)
+            .chain(self.shim().map(ModuleItem::Stmt))
+            .collect()
+    }
+
+    /// The code declaring `tr_ch_apm_tracingChannel` at the start of a script.
+    pub(crate) fn script_prologue(&self, dc_module: &str) -> Vec<Stmt> {
+        let require = match self {
+            TracingBackend::DiagnosticsChannel => Some(quote!(
+                "const { tracingChannel: tr_ch_apm_tracingChannel } = require($dc);" as Stmt,
+                dc: Expr = dc_module.into(),
+            )),
+            TracingBackend::GlobalHook { .. } => None,
+            TracingBackend::OpenTelemetry { module, .. } => Some(quote!(
+                "const tr_ch_apm_otel = require($otel);" as Stmt,
+                otel: Expr = module.as_str().into(),
+            )),
+        };
+        require.into_iter().chain(self.shim()).collect()
+    }
+
+    /// Declares `tr_ch_apm_tracingChannel` for backends other than `diagnostics_channel`, with
+    /// just what the generated code uses of it.
+    fn shim(&self) -> Option<Stmt> {
+        match self {
+            TracingBackend::DiagnosticsChannel => None,
+            TracingBackend::GlobalHook { key } => Some(global_hook_shim(key)),
+            TracingBackend::OpenTelemetry { tracer_name, .. } => {
+                Some(open_telemetry_shim(tracer_name))
+            }
+        }
+    }
+}
+
+fn global_hook_shim(key: &str) -> Stmt {
+    quote!(
+        "const tr_ch_apm_tracingChannel = (name) => {
+            const hooks = () => globalThis[Symbol.for($key)]?.[name];
+            const event = (event) => ({
+                publish (context) {
+                    hooks()?.[event]?.(context);
+                }
+            });
+            const start = event('start');
+            const end = event('end');
+            const asyncStart = event('asyncStart');
+            const asyncEnd = event('asyncEnd');
+            const error = event('error');
+            return {
+                get hasSubscribers () {
+                    return hooks() !== undefined;
+                },
+                start,
+                end,
+                asyncStart,
+                asyncEnd,
+                error,
+                traceSync (fn, context = {}, thisArg, ...args) {
+                    start.publish(context);
+                    try {
+                        return context.result = Reflect.apply(fn, thisArg, args);
+                    } catch (err) {
+                        context.error = err;
+                        error.publish(context);
+                        throw err;
+                    } finally {
+                        end.publish(context);
+                    }
+                },
+                tracePromise (fn, context = {}, thisArg, ...args) {
+                    start.publish(context);
+                    try {
+                        return Promise.resolve(Reflect.apply(fn, thisArg, args)).then(
+                            (result) => {
+                                context.result = result;
+                                asyncStart.publish(context);
+                                asyncEnd.publish(context);
+                                return result;
+                            },
+                            (err) => {
+                                context.error = err;
+                                error.publish(context);
+                                asyncStart.publish(context);
+                                asyncEnd.publish(context);
+                                throw err;
+                            }
+                        );
+                    } catch (err) {
+                        context.error = err;
+                        error.publish(context);
+                        throw err;
+                    } finally {
+                        end.publish(context);
+                    }
+                }
+            };
+        };" as Stmt,
+        key: Expr = key.into(),
+    )
+}
+
+fn open_telemetry_shim(tracer_name: &str) -> Stmt {
+    quote!(
+        "const tr_ch_apm_tracingChannel = (name) => {
+            const tracer = tr_ch_apm_otel.trace.getTracer($tracer_name);
+            const fail = (span, err) => {
+                span.recordException(err);
+                span.setStatus({
+                    code: tr_ch_apm_otel.SpanStatusCode.ERROR,
+                    message: err?.message
+                });
+            };
+            const trace = (fn, context, thisArg, args, settle) => tracer.startActiveSpan(
+                name,
+                (span) => {
+                    context.span = span;
+                    try {
+                        return settle(Reflect.apply(fn, thisArg, args), span);
+                    } catch (err) {
+                        context.error = err;
+                        fail(span, err);
+                        span.end();
+                        throw err;
+                    }
+                }
+            );
+            return {
+                hasSubscribers: true,
+                start: {
+                    publish (context) {
+                        context.span = tracer.startSpan(name);
+                    }
+                },
+                end: {
+                    publish (context) {
+                        context.span?.end();
+                    }
+                },
+                error: {
+                    publish (context) {
+                        if (context.span) fail(context.span, context.error);
+                    }
+                },
+                traceSync (fn, context = {}, thisArg, ...args) {
+                    return trace(fn, context, thisArg, args, (result, span) => {
+                        context.result = result;
+                        span.end();
+                        return result;
+                    });
+                },
+                tracePromise (fn, context = {}, thisArg, ...args) {
+                    return trace(fn, context, thisArg, args, (promise, span) => Promise
+                        .resolve(promise)
+                        .then(
+                            (result) => {
+                                context.result = result;
+                                span.end();
+                                return result;
+                            },
+                            (err) => {
+                                context.error = err;
+                                fail(span, err);
+                                span.end();
+                                throw err;
+                            }
+                        ));
+                }
+            };
+        };" as Stmt,
+        tracer_name: Expr = tracer_name.into(),
+    )
+}
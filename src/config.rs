@@ -2,7 +2,12 @@
  * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
  * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
  **/
+use crate::backend::TracingBackend;
+use crate::context_builder::ContextBuilder;
+use crate::error::OrchestrionError;
 use crate::function_query::FunctionQuery;
+use crate::syntax::SourceSyntax;
+use crate::template::CodeTemplate;
 use nodejs_semver::{Range, SemverError, Version};
 use std::path::PathBuf;
 
@@ -41,21 +46,170 @@ impl ModuleMatcher {
     }
 }
 
+/// How the code that traces a function is generated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Codegen {
+    /// Moves the body of each function into an arrow function created on every call, which is
+    /// then traced. This works for any function, but calls pay for allocating the arrow function
+    /// even when nothing is subscribed to the channel.
+    #[default]
+    Closure,
+    /// Moves the body of each function into a separate function declared next to it, so calls
+    /// with nothing subscribed are a single branch and a direct call. Functions whose body
+    /// couldn't keep its meaning elsewhere, because it uses `super`, `new.target` or private
+    /// names, has parameters with defaults or patterns, or is in a scope a declaration can't be
+    /// added to, such as an arrow function's expression body, are traced as with
+    /// [`Codegen::Closure`] instead.
+    Hoisted,
+}
+
 #[derive(Debug, Clone)]
 pub struct InstrumentationConfig {
+    /// The name of the channel, which is published on as `orchestrion:<module>:<channel_name>`.
+    /// `{name}` and `{class}` are replaced with the names of each matched function and its
+    /// class, so a query with name patterns can publish each match on a distinct channel.
     pub channel_name: String,
     pub module: ModuleMatcher,
-    pub function_query: FunctionQuery,
+    /// The functions to trace. Functions matched by any of these queries are all published on
+    /// the same channel, so one operation implemented in several places, such as `Client.query`
+    /// and `Pool.query`, only needs one channel. Each query counts its own `index`.
+    pub function_queries: Vec<FunctionQuery>,
+    pub codegen: Codegen,
+    /// Whether the parameters of traced functions and constructors are bound again from the
+    /// context's `arguments` once `start` has been published, so subscribers can change what the
+    /// function is called with, by assigning to `context.arguments[i]` or replacing
+    /// `context.arguments` altogether. With [`Codegen::Closure`], the parameters are assigned
+    /// as if by destructuring, so defaults of arguments that are still `undefined` are evaluated
+    /// again. `context.arguments` starts out as the function's own `arguments` object, which
+    /// assigning to `context.arguments[i]` changes too, but which doesn't follow a replaced
+    /// `context.arguments`, except where sloppy mode functions with simple parameter lists keep it
+    /// in sync with the rebound parameters.
+    pub rebind_parameters: bool,
+    /// Whether subscribers can skip or replace what traced functions and constructors return.
+    /// When `context.skip` is set by `start`, the original body doesn't run, and `context.result`
+    /// is returned instead. Otherwise, `context.result` is returned once the function completes,
+    /// so it can be replaced by `end`, or by `asyncEnd` for async functions. Constructors only
+    /// return `context.result` when it isn't `undefined`, and a derived class's constructor that
+    /// is skipped must be given an object to return, since `super()` never ran.
+    pub around_advice: bool,
+    /// Whether the context published for each traced function has a `site` with static metadata
+    /// about where it was traced: the `module` name and `version`, the `file` path within the
+    /// module, the `query` that matched the function, and the `line` and `column` it starts at,
+    /// if a source map was set. The metadata is declared once per function as a frozen object.
+    pub site_metadata: bool,
+    /// An expression whose properties are added to the context published for traced functions,
+    /// other than constructors. See [`ContextBuilder`].
+    pub context_builder: Option<ContextBuilder>,
+    /// The code to replace the body of traced functions with, instead of publishing to their
+    /// channel. See [`CodeTemplate`] for what it can't be combined with.
+    pub template: Option<CodeTemplate>,
 }
 
 impl InstrumentationConfig {
     #[must_use]
     pub fn new(channel_name: &str, module: ModuleMatcher, function_query: FunctionQuery) -> Self {
+        Self::with_queries(channel_name, module, vec![function_query])
+    }
+
+    #[must_use]
+    pub fn with_queries(
+        channel_name: &str,
+        module: ModuleMatcher,
+        function_queries: Vec<FunctionQuery>,
+    ) -> Self {
         Self {
             channel_name: channel_name.to_string(),
             module,
-            function_query,
+            function_queries,
+            codegen: Codegen::default(),
+            rebind_parameters: false,
+            around_advice: false,
+            site_metadata: false,
+            context_builder: None,
+            template: None,
+        }
+    }
+
+    /// Generates the tracing code of matched functions as described by `codegen`.
+    #[must_use]
+    pub fn with_codegen(mut self, codegen: Codegen) -> Self {
+        self.codegen = codegen;
+        self
+    }
+
+    /// Binds the parameters of traced functions again once `start` has been published. See
+    /// [`InstrumentationConfig::rebind_parameters`].
+    #[must_use]
+    pub fn with_rebound_parameters(mut self) -> Self {
+        self.rebind_parameters = true;
+        self
+    }
+
+    /// Lets subscribers skip traced functions or replace their results. See
+    /// [`InstrumentationConfig::around_advice`].
+    #[must_use]
+    pub fn with_around_advice(mut self) -> Self {
+        self.around_advice = true;
+        self
+    }
+
+    /// Adds static metadata about each traced function to its context. See
+    /// [`InstrumentationConfig::site_metadata`].
+    #[must_use]
+    pub fn with_site_metadata(mut self) -> Self {
+        self.site_metadata = true;
+        self
+    }
+
+    /// Adds the properties of `context_builder` to the context of traced functions.
+    #[must_use]
+    pub fn with_context_builder(mut self, context_builder: ContextBuilder) -> Self {
+        self.context_builder = Some(context_builder);
+        self
+    }
+
+    /// Traces functions with `template` rather than the default code.
+    #[must_use]
+    pub fn with_template(mut self, template: CodeTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// Checks that this instrumentation has queries and that every one of them is valid, which
+    /// queries built in code rather than parsed haven't been checked for, and that its options
+    /// can be combined.
+    /// # Errors
+    /// Returns an error if there are no queries, if a query has an invalid name pattern, or if a
+    /// [`CodeTemplate`] is combined with options it doesn't support.
+    pub fn validate(&self) -> Result<(), OrchestrionError> {
+        if self.function_queries.is_empty() {
+            return Err(format!("No function queries for channel {:?}", self.channel_name).into());
+        }
+        for query in &self.function_queries {
+            query.validate()?;
+        }
+        if self.template.is_some() {
+            let unsupported = [
+                (self.codegen == Codegen::Hoisted, "hoisted codegen"),
+                (self.rebind_parameters, "rebound parameters"),
+                (self.around_advice, "around advice"),
+                (self.site_metadata, "site metadata"),
+                (
+                    self.function_queries
+                        .iter()
+                        .any(FunctionQuery::traces_constructors),
+                    "constructor queries",
+                ),
+            ];
+            if let Some((_, option)) = unsupported.iter().find(|(enabled, _)| *enabled) {
+                return Err(format!(
+                    "Code templates can't be combined with {option}, for channel {:?}",
+                    self.channel_name
+                )
+                .into());
+            }
         }
+        Ok(())
     }
 }
 
@@ -63,6 +217,13 @@ impl InstrumentationConfig {
 pub struct Config {
     pub instrumentations: Vec<InstrumentationConfig>,
     pub dc_module: String,
+    /// The syntax to parse every file with, instead of detecting it from each file. See
+    /// [`Instrumentor::source_syntax`].
+    ///
+    /// [`Instrumentor::source_syntax`]: crate::Instrumentor::source_syntax
+    pub syntax: Option<SourceSyntax>,
+    /// What the generated code traces functions with. See [`TracingBackend`].
+    pub backend: TracingBackend,
 }
 
 impl Config {
@@ -71,13 +232,39 @@ impl Config {
         Self {
             instrumentations,
             dc_module: dc_module.unwrap_or_else(|| "diagnostics_channel".to_string()),
+            syntax: None,
+            backend: TracingBackend::default(),
         }
     }
 
+    /// Parses every file with the given syntax, rather than detecting it from each file.
+    #[must_use]
+    pub fn with_syntax(mut self, syntax: SourceSyntax) -> Self {
+        self.syntax = Some(syntax);
+        self
+    }
+
+    /// Traces functions with the given backend, rather than `diagnostics_channel`.
+    #[must_use]
+    pub fn with_backend(mut self, backend: TracingBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
     #[must_use]
     pub fn new_single(instrumentation: InstrumentationConfig) -> Self {
         Self::new(vec![instrumentation], None)
     }
+
+    /// Checks that every instrumentation is valid. See [`InstrumentationConfig::validate`].
+    /// # Errors
+    /// Returns an error if any instrumentation is invalid.
+    pub fn validate(&self) -> Result<(), OrchestrionError> {
+        for instrumentation in &self.instrumentations {
+            instrumentation.validate()?;
+        }
+        Ok(())
+    }
 }
 
 impl InstrumentationConfig {
@@ -0,0 +1,70 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
+ **/
+use crate::error::OrchestrionError;
+use crate::syntax::parse_snippet;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use swc_core::common::Span;
+use swc_core::ecma::ast::{Expr, PropOrSpread, SpreadElement, Stmt};
+
+/// A JavaScript expression that adds to the context published for each traced function, so that
+/// subscribers don't need to know which argument is which. It's parsed with `.parse()`, which
+/// fails if it isn't a single expression.
+///
+/// The expression is evaluated in the traced function, before `start` is published, so it can
+/// read the function's named parameters as well as `arguments` and `this`. If it's an object
+/// literal, its properties are added to the context, as with
+/// `{ url: arguments[0]?.href ?? arguments[0], method: options?.method }`. The properties of any
+/// other expression are spread into the context. If evaluating it throws, nothing is added to the
+/// context, and the function runs as it would otherwise.
+#[derive(Debug, Clone)]
+pub struct ContextBuilder {
+    source: String,
+    props: Vec<PropOrSpread>,
+}
+
+impl ContextBuilder {
+    /// The properties to add to the context object.
+    pub(crate) fn props(&self) -> &[PropOrSpread] {
+        &self.props
+    }
+}
+
+impl Display for ContextBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl FromStr for ContextBuilder {
+    type Err = OrchestrionError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| -> OrchestrionError {
+            format!("Invalid context builder {input:?}: {reason}").into()
+        };
+        // Wrapped in parentheses, so an object literal isn't parsed as a block, and so anything
+        // after the expression ends up outside of them.
+        let mut stmts = parse_snippet(&format!("({input}\n);")).map_err(|e| invalid(&e))?;
+        let expr = match stmts.pop() {
+            Some(Stmt::Expr(stmt)) if stmts.is_empty() => stmt.expr,
+            _ => return Err(invalid("must be a single expression")),
+        };
+        let Expr::Paren(paren) = *expr else {
+            return Err(invalid("must be a single expression"));
+        };
+        let props = match *paren.expr {
+            Expr::Object(object) => object.props,
+            expr => vec![PropOrSpread::Spread(SpreadElement {
+                dot3_token: Span::default(),
+                expr: Box::new(expr),
+            })],
+        };
+        Ok(ContextBuilder {
+            source: input.to_string(),
+            props,
+        })
+    }
+}
@@ -0,0 +1,384 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
+ **/
+use crate::analysis::{Analysis, AnalysisOptions};
+use swc_core::common::Span;
+use swc_core::ecma::{
+    ast::{
+        AssignExpr, BigInt, BinExpr, BinaryOp, Bool, ClassMember, ComputedPropName, Decl, Expr,
+        Function, IdentName, Lit, MetaPropExpr, MetaPropKind, Null, Number, Pat, PrivateName,
+        Program, Prop, Regex, Stmt, Str, TplElement, UnaryExpr, UnaryOp, UpdateExpr, VarDecl,
+    },
+    visit::{Visit, VisitWith},
+};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Hashes what a minifier or bundler can't change without changing what a function does: the
+/// kinds of its nodes, its operators, property names, and the values of its literals. Positions,
+/// the names of variables and parameters, and how literals are spelled, even as `!0` rather than
+/// `true`, are left out.
+struct Fingerprinter {
+    hash: u64,
+}
+
+impl Fingerprinter {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash = (self.hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME);
+        }
+        // Ends every token with a byte that isn't valid UTF-8, so tokens can't run together.
+        self.hash = (self.hash ^ 0xff).wrapping_mul(FNV_PRIME);
+    }
+
+    fn write_str(&mut self, token: &str) {
+        self.write(token.as_bytes());
+    }
+}
+
+impl Visit for Fingerprinter {
+    fn visit_function(&mut self, node: &Function) {
+        self.write_str("function");
+        self.write(&[u8::from(node.is_async), u8::from(node.is_generator)]);
+        node.visit_children_with(self);
+    }
+
+    fn visit_stmt(&mut self, node: &Stmt) {
+        self.write_str(stmt_kind(node));
+        node.visit_children_with(self);
+    }
+
+    fn visit_decl(&mut self, node: &Decl) {
+        self.write_str(decl_kind(node));
+        node.visit_children_with(self);
+    }
+
+    fn visit_var_decl(&mut self, node: &VarDecl) {
+        self.write_str(node.kind.as_str());
+        node.visit_children_with(self);
+    }
+
+    fn visit_expr(&mut self, node: &Expr) {
+        if let Some(literal) = MinifiedLiteral::of(node) {
+            self.write_str("literal");
+            match literal {
+                MinifiedLiteral::Bool(value) => self.visit_bool(&value.into()),
+                MinifiedLiteral::Number(value) => self.visit_number(&value.into()),
+                MinifiedLiteral::Undefined => self.write_str("undefined"),
+            }
+            return;
+        }
+        self.write_str(expr_kind(node));
+        node.visit_children_with(self);
+    }
+
+    fn visit_pat(&mut self, node: &Pat) {
+        self.write_str(pat_kind(node));
+        node.visit_children_with(self);
+    }
+
+    fn visit_prop(&mut self, node: &Prop) {
+        self.write_str(prop_kind(node));
+        node.visit_children_with(self);
+    }
+
+    fn visit_class_member(&mut self, node: &ClassMember) {
+        self.write_str(class_member_kind(node));
+        node.visit_children_with(self);
+    }
+
+    fn visit_computed_prop_name(&mut self, node: &ComputedPropName) {
+        self.write_str("computed");
+        node.visit_children_with(self);
+    }
+
+    fn visit_bin_expr(&mut self, node: &BinExpr) {
+        self.write_str(node.op.as_str());
+        node.visit_children_with(self);
+    }
+
+    fn visit_unary_expr(&mut self, node: &UnaryExpr) {
+        self.write_str(node.op.as_str());
+        node.visit_children_with(self);
+    }
+
+    fn visit_update_expr(&mut self, node: &UpdateExpr) {
+        self.write_str(node.op.as_str());
+        self.write(&[u8::from(node.prefix)]);
+        node.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, node: &AssignExpr) {
+        self.write_str(node.op.as_str());
+        node.visit_children_with(self);
+    }
+
+    fn visit_meta_prop_expr(&mut self, node: &MetaPropExpr) {
+        self.write_str(match node.kind {
+            MetaPropKind::NewTarget => "new.target",
+            MetaPropKind::ImportMeta => "import.meta",
+        });
+    }
+
+    fn visit_ident_name(&mut self, node: &IdentName) {
+        self.write_str(&node.sym);
+    }
+
+    fn visit_private_name(&mut self, node: &PrivateName) {
+        self.write_str("#");
+        self.write_str(&node.name);
+    }
+
+    fn visit_str(&mut self, node: &Str) {
+        self.write_str("string");
+        self.write_str(&node.value);
+    }
+
+    fn visit_number(&mut self, node: &Number) {
+        self.write_str("number");
+        self.write(&node.value.to_bits().to_le_bytes());
+    }
+
+    fn visit_big_int(&mut self, node: &BigInt) {
+        self.write_str("bigint");
+        self.write_str(&node.value.to_string());
+    }
+
+    fn visit_bool(&mut self, node: &Bool) {
+        self.write_str(if node.value { "true" } else { "false" });
+    }
+
+    fn visit_null(&mut self, _node: &Null) {
+        self.write_str("null");
+    }
+
+    fn visit_regex(&mut self, node: &Regex) {
+        self.write_str("regex");
+        self.write_str(&node.exp);
+        self.write_str(&node.flags);
+    }
+
+    fn visit_tpl_element(&mut self, node: &TplElement) {
+        self.write_str("quasi");
+        self.write_str(node.cooked.as_ref().unwrap_or(&node.raw));
+    }
+}
+
+/// A value that minifiers write as an expression other than its literal, such as `!0` for `true`,
+/// `void 0` for `undefined`, or `1/0` for `Infinity`, so it's hashed the same either way.
+enum MinifiedLiteral {
+    Bool(bool),
+    Number(f64),
+    Undefined,
+}
+
+impl MinifiedLiteral {
+    fn of(node: &Expr) -> Option<Self> {
+        match node {
+            Expr::Unary(UnaryExpr { op, arg, .. }) => match (op, number(arg)) {
+                (UnaryOp::Bang, Some(value)) => Some(Self::Bool(value == 0.0 || value.is_nan())),
+                (UnaryOp::Void, Some(_)) => Some(Self::Undefined),
+                _ => None,
+            },
+            Expr::Bin(BinExpr {
+                op: BinaryOp::Div,
+                left,
+                right,
+                ..
+            }) => match (number(left), number(right)) {
+                (Some(left), Some(right)) if right == 0.0 => Some(Self::Number(left / right)),
+                _ => None,
+            },
+            // These globals are hashed as what they are, unlike other identifiers, which
+            // minifiers rename.
+            Expr::Ident(ident) => match &*ident.sym {
+                "undefined" => Some(Self::Undefined),
+                "Infinity" => Some(Self::Number(f64::INFINITY)),
+                "NaN" => Some(Self::Number(f64::NAN)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+fn number(node: &Expr) -> Option<f64> {
+    match node {
+        Expr::Lit(Lit::Num(number)) => Some(number.value),
+        _ => None,
+    }
+}
+
+fn stmt_kind(node: &Stmt) -> &'static str {
+    match node {
+        Stmt::Block(_) => "block",
+        Stmt::Empty(_) => "empty",
+        Stmt::Debugger(_) => "debugger",
+        Stmt::With(_) => "with",
+        Stmt::Return(_) => "return",
+        Stmt::Labeled(_) => "labeled",
+        Stmt::Break(_) => "break",
+        Stmt::Continue(_) => "continue",
+        Stmt::If(_) => "if",
+        Stmt::Switch(_) => "switch",
+        Stmt::Throw(_) => "throw",
+        Stmt::Try(_) => "try",
+        Stmt::While(_) => "while",
+        Stmt::DoWhile(_) => "do-while",
+        Stmt::For(_) => "for",
+        Stmt::ForIn(_) => "for-in",
+        Stmt::ForOf(_) => "for-of",
+        Stmt::Decl(_) => "declaration",
+        Stmt::Expr(_) => "expression",
+    }
+}
+
+fn decl_kind(node: &Decl) -> &'static str {
+    match node {
+        Decl::Class(_) => "class",
+        Decl::Fn(_) => "function",
+        Decl::Var(_) => "var",
+        Decl::Using(_) => "using",
+        Decl::TsInterface(_) | Decl::TsTypeAlias(_) | Decl::TsEnum(_) | Decl::TsModule(_) => "type",
+    }
+}
+
+fn expr_kind(node: &Expr) -> &'static str {
+    match node {
+        Expr::This(_) => "this",
+        Expr::Array(_) => "array",
+        Expr::Object(_) => "object",
+        Expr::Fn(_) => "function",
+        Expr::Unary(_) => "unary",
+        Expr::Update(_) => "update",
+        Expr::Bin(_) => "binary",
+        Expr::Assign(_) => "assign",
+        Expr::Member(_) => "member",
+        Expr::SuperProp(_) => "super",
+        Expr::Cond(_) => "conditional",
+        Expr::Call(_) => "call",
+        Expr::New(_) => "new",
+        Expr::Seq(_) => "sequence",
+        Expr::Ident(_) => "identifier",
+        Expr::Lit(_) => "literal",
+        Expr::Tpl(_) => "template",
+        Expr::TaggedTpl(_) => "tagged-template",
+        Expr::Arrow(_) => "arrow",
+        Expr::Class(_) => "class",
+        Expr::Yield(_) => "yield",
+        Expr::MetaProp(_) => "meta-property",
+        Expr::Await(_) => "await",
+        Expr::Paren(_) => "parenthesized",
+        Expr::JSXMember(_)
+        | Expr::JSXNamespacedName(_)
+        | Expr::JSXEmpty(_)
+        | Expr::JSXElement(_)
+        | Expr::JSXFragment(_) => "jsx",
+        Expr::TsTypeAssertion(_)
+        | Expr::TsConstAssertion(_)
+        | Expr::TsNonNull(_)
+        | Expr::TsAs(_)
+        | Expr::TsInstantiation(_)
+        | Expr::TsSatisfies(_) => "type-assertion",
+        Expr::PrivateName(_) => "private-name",
+        Expr::OptChain(_) => "optional-chain",
+        Expr::Invalid(_) => "invalid",
+    }
+}
+
+fn pat_kind(node: &Pat) -> &'static str {
+    match node {
+        Pat::Ident(_) => "binding",
+        Pat::Array(_) => "array-pattern",
+        Pat::Rest(_) => "rest",
+        Pat::Object(_) => "object-pattern",
+        Pat::Assign(_) => "default",
+        Pat::Invalid(_) => "invalid",
+        Pat::Expr(_) => "expression-pattern",
+    }
+}
+
+fn prop_kind(node: &Prop) -> &'static str {
+    match node {
+        Prop::Shorthand(_) => "shorthand",
+        Prop::KeyValue(_) => "key-value",
+        Prop::Assign(_) => "assign-property",
+        Prop::Getter(_) => "getter",
+        Prop::Setter(_) => "setter",
+        Prop::Method(_) => "method",
+    }
+}
+
+fn class_member_kind(node: &ClassMember) -> &'static str {
+    match node {
+        ClassMember::Constructor(_) => "constructor",
+        ClassMember::Method(_) => "method",
+        ClassMember::PrivateMethod(_) => "private-method",
+        ClassMember::ClassProp(_) => "property",
+        ClassMember::PrivateProp(_) => "private-property",
+        ClassMember::TsIndexSignature(_) => "index-signature",
+        ClassMember::Empty(_) => "empty",
+        ClassMember::StaticBlock(_) => "static-block",
+        ClassMember::AutoAccessor(_) => "accessor",
+    }
+}
+
+/// Computes the structural fingerprint of a function, as matched by
+/// [`FunctionQuery::Fingerprint`]. Two functions have the same fingerprint if they only differ
+/// in the names of their variables and parameters, their formatting, or how their literals are
+/// written, so a fingerprint taken from a readable build of a library also matches the same
+/// function in a minified build of it.
+///
+/// Fingerprints are derived from SWC's AST, so they may change between versions of this crate.
+///
+/// [`FunctionQuery::Fingerprint`]: crate::FunctionQuery::Fingerprint
+#[must_use]
+pub fn function_fingerprint(func: &Function) -> String {
+    let mut fingerprinter = Fingerprinter {
+        hash: FNV_OFFSET_BASIS,
+    };
+    func.visit_with(&mut fingerprinter);
+    format!("{:016x}", fingerprinter.hash)
+}
+
+/// A function found by [`function_fingerprints`].
+#[derive(Debug, Clone)]
+pub struct FunctionFingerprint {
+    /// The name the function is declared or assigned with, if it has one.
+    pub name: Option<String>,
+    /// The names of the named functions this one is nested in, outermost first.
+    pub enclosing: Vec<String>,
+    pub span: Span,
+    pub fingerprint: String,
+}
+
+/// Computes the fingerprint of every function in a program, in source order. This is meant for
+/// authoring [`FunctionQuery::Fingerprint`] queries from a readable build of a library, where
+/// functions can still be told apart by their names.
+///
+/// [`FunctionQuery::Fingerprint`]: crate::FunctionQuery::Fingerprint
+#[must_use]
+pub fn function_fingerprints(program: &Program) -> Vec<FunctionFingerprint> {
+    let analysis = Analysis::new(
+        program.clone(),
+        &AnalysisOptions {
+            fingerprints: true,
+            ..Default::default()
+        },
+    );
+    let mut fingerprints: Vec<FunctionFingerprint> = analysis
+        .functions()
+        .filter_map(|info| {
+            Some(FunctionFingerprint {
+                name: info.name.clone(),
+                enclosing: info.enclosing.clone(),
+                span: info.span,
+                fingerprint: info.fingerprint.clone()?,
+            })
+        })
+        .collect();
+    fingerprints.sort_by_key(|function| function.span.lo);
+    fingerprints
+}
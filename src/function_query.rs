@@ -2,13 +2,87 @@
  * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
  * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
  **/
-use swc_core::ecma::ast::{FnDecl, FnExpr, Function};
+use crate::analysis::{member_segments, FunctionInfo};
+use crate::error::OrchestrionError;
+use crate::helpers::{helper_name, unwrap_callee};
+use crate::selector::Selector;
+use regex::Regex;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use swc_core::ecma::ast::{
+    Callee, Constructor, Decorator, Expr, ExprOrSpread, FnDecl, FnExpr, Function, Lit,
+    ParamOrTsParamProp, Pat, ReturnStmt, Stmt, TsParamPropParam,
+};
+
+thread_local! {
+    /// Compiled regular expressions for name patterns, so they're only compiled once per thread.
+    static REGEX_CACHE: RefCell<HashMap<String, Regex>> = RefCell::new(HashMap::new());
+}
+
+/// Matches a name against a name field of a [`FunctionQuery`], which may be a pattern.
+pub(crate) fn name_matches(pattern: &str, name: &str) -> bool {
+    if let Some(regex) = regex_pattern(pattern) {
+        return REGEX_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            if let Some(compiled) = cache.get(regex) {
+                return compiled.is_match(name);
+            }
+            // Patterns are checked when the `Instrumentor` is created, with `validate_name`.
+            let Ok(compiled) = Regex::new(regex) else {
+                return false;
+            };
+            let matches = compiled.is_match(name);
+            cache.insert(regex.to_string(), compiled);
+            matches
+        });
+    }
+    if pattern.contains(['*', '?']) {
+        return wildcard_matches(pattern.as_bytes(), name.as_bytes());
+    }
+    pattern == name
+}
+
+/// Checks that a name field of a [`FunctionQuery`] is a valid pattern, if it's a pattern at all.
+pub(crate) fn validate_name(name: &str) -> Result<(), OrchestrionError> {
+    if let Some(regex) = regex_pattern(name) {
+        Regex::new(regex).map_err(|e| format!("Invalid name pattern {name}: {e}"))?;
+    }
+    Ok(())
+}
+
+fn regex_pattern(pattern: &str) -> Option<&str> {
+    pattern
+        .strip_prefix('/')
+        .and_then(|pattern| pattern.strip_suffix('/'))
+}
+
+fn wildcard_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            wildcard_matches(&pattern[1..], name)
+                || (!name.is_empty() && wildcard_matches(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => wildcard_matches(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => wildcard_matches(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
 
 #[derive(Debug, Clone)]
 pub(crate) enum FunctionType {
     FunctionDeclaration,
     FunctionExpression,
     Method,
+    ConstructorFunction,
+    Export,
+    Location,
+    Fingerprint,
+    Selector,
+    Alternatives,
 }
 
 #[derive(Debug, Clone)]
@@ -23,11 +97,16 @@ impl FunctionKind {
         matches!(self, FunctionKind::Async)
     }
 
+    /// Whether a function is of this kind. Async functions compiled for older runtimes by
+    /// TypeScript or Babel, which return the result of a helper such as `__awaiter` or
+    /// `_asyncToGenerator`, are async as well. Those Babel compiles to functions delegating to
+    /// another one, as it does async function declarations, aren't.
     #[must_use]
     pub fn matches(&self, func: &Function) -> bool {
+        let is_async = func.is_async || is_downleveled_async(func);
         match self {
-            FunctionKind::Sync => !func.is_async && !func.is_generator,
-            FunctionKind::Async => func.is_async && !func.is_generator,
+            FunctionKind::Sync => !is_async && !func.is_generator,
+            FunctionKind::Async => is_async && !func.is_generator,
         }
     }
 
@@ -40,6 +119,351 @@ impl FunctionKind {
     }
 }
 
+/// The helpers that TypeScript and Babel compile async functions to calls of, as named by
+/// [`helper_name`]. TypeScript's `__generator`, used when targeting ES5, is only ever called
+/// within `__awaiter`.
+const ASYNC_HELPERS: [&str; 2] = ["awaiter", "asyncToGenerator"];
+
+/// Whether a function is an async function compiled for older runtimes, which ends by returning
+/// a call of one of the [`ASYNC_HELPERS`], as in `return __awaiter(this, void 0, void 0,
+/// function* () {...})` or `return _asyncToGenerator(function* () {...})()`. The helpers can be
+/// inlined or imported.
+///
+/// Babel only compiles methods that stay methods to that. It turns async function declarations,
+/// and methods of the classes it compiles too, into functions that delegate to another one, as
+/// in `return _fetch.apply(this, arguments)`, where `_fetch` is assigned the helper's result
+/// elsewhere. Those can't be told apart from any other delegating function, so they're only
+/// matched as sync functions.
+fn is_downleveled_async(func: &Function) -> bool {
+    let Some(Stmt::Return(ReturnStmt { arg: Some(arg), .. })) =
+        func.body.as_ref().and_then(|body| body.stmts.last())
+    else {
+        return false;
+    };
+    let Expr::Call(call) = unwrap_callee(arg) else {
+        return false;
+    };
+    let Callee::Expr(callee) = &call.callee else {
+        return false;
+    };
+    // Babel's helper returns a function, which is either called directly or with `.call` or
+    // `.apply`.
+    let callee = match unwrap_callee(callee) {
+        Expr::Call(inner) => inner.callee.as_expr(),
+        Expr::Member(member) => match unwrap_callee(&member.obj) {
+            Expr::Call(inner) => inner.callee.as_expr(),
+            _ => Some(callee),
+        },
+        _ => Some(callee),
+    };
+    callee
+        .and_then(|callee| helper_name(callee))
+        .is_some_and(|name| ASYNC_HELPERS.contains(&name))
+}
+
+/// Restricts where in a program a [`FunctionQuery`] looks for functions, so that matches don't
+/// have to be disambiguated by `index`. Scopes are determined by running SWC's resolver on a copy
+/// of the program before it's instrumented.
+#[derive(Debug, Clone, Default)]
+pub struct FunctionScope {
+    /// Only match functions that aren't nested inside any other function.
+    pub top_level: bool,
+    /// Only match functions whose innermost enclosing named functions have these names,
+    /// outermost first. Anonymous functions, such as callbacks, are skipped over.
+    pub enclosing: Vec<String>,
+    /// Skip functions declared or assigned to a binding that shadows a binding of the same name
+    /// in an enclosing scope.
+    pub unshadowed: bool,
+}
+
+impl FunctionScope {
+    #[must_use]
+    pub fn top_level() -> Self {
+        FunctionScope {
+            top_level: true,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a scope from a path of enclosing function names, such as
+    /// `createClient > request`.
+    #[must_use]
+    pub fn within(path: &str) -> Self {
+        FunctionScope {
+            enclosing: path
+                .split('>')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[must_use]
+    pub fn unshadowed(mut self) -> Self {
+        self.unshadowed = true;
+        self
+    }
+
+    pub(crate) fn matches(&self, info: &FunctionInfo) -> bool {
+        (!self.top_level || info.top_level)
+            && (!self.unshadowed || !info.shadows)
+            && info.enclosing.ends_with(&self.enclosing)
+    }
+}
+
+/// The shape of a single parameter, as far as a [`FunctionSignature`] is concerned.
+pub(crate) struct ParamShape {
+    name: String,
+    is_rest: bool,
+    has_default: bool,
+}
+
+impl ParamShape {
+    fn of_pat(pat: &Pat) -> Self {
+        match pat {
+            Pat::Ident(ident) => ParamShape {
+                name: ident.id.sym.to_string(),
+                is_rest: false,
+                has_default: false,
+            },
+            Pat::Assign(assign) => ParamShape {
+                has_default: true,
+                ..ParamShape::of_pat(&assign.left)
+            },
+            Pat::Rest(rest) => ParamShape {
+                is_rest: true,
+                ..ParamShape::of_pat(&rest.arg)
+            },
+            // Destructured parameters don't have a name.
+            _ => ParamShape {
+                name: String::new(),
+                is_rest: false,
+                has_default: false,
+            },
+        }
+    }
+
+    pub(crate) fn of_function(func: &Function) -> Vec<Self> {
+        func.params
+            .iter()
+            // TypeScript's `this` parameter only declares the type of `this`.
+            .filter(|param| !matches!(&param.pat, Pat::Ident(ident) if ident.id.sym == "this"))
+            .map(|param| ParamShape::of_pat(&param.pat))
+            .collect()
+    }
+
+    pub(crate) fn of_constructor(constructor: &Constructor) -> Vec<Self> {
+        constructor
+            .params
+            .iter()
+            .map(|param| match param {
+                ParamOrTsParamProp::Param(param) => ParamShape::of_pat(&param.pat),
+                ParamOrTsParamProp::TsParamProp(prop) => match &prop.param {
+                    TsParamPropParam::Ident(ident) => ParamShape {
+                        name: ident.id.sym.to_string(),
+                        is_rest: false,
+                        has_default: false,
+                    },
+                    TsParamPropParam::Assign(assign) => ParamShape {
+                        has_default: true,
+                        ..ParamShape::of_pat(&assign.left)
+                    },
+                },
+            })
+            .collect()
+    }
+}
+
+/// Constrains the parameters and flags of the functions a [`FunctionQuery`] matches, so that
+/// same-named functions can be told apart by what they take rather than by `index`. Every field
+/// left as `None` matches anything.
+#[derive(Debug, Clone, Default)]
+pub struct FunctionSignature {
+    /// The number of parameters, including a rest parameter.
+    pub param_count: Option<usize>,
+    /// The names of the parameters, in order. These can be patterns, like the name fields of
+    /// [`FunctionQuery`]. Destructured parameters have an empty name, so they're only matched by
+    /// patterns such as `*`.
+    pub param_names: Option<Vec<String>>,
+    /// Whether the last parameter is a rest parameter.
+    pub has_rest: Option<bool>,
+    /// Whether any parameter has a default value.
+    pub has_default: Option<bool>,
+    /// Whether the function is `async`. This is checked in addition to the query's
+    /// [`FunctionKind`].
+    pub is_async: Option<bool>,
+    /// Whether the function is a generator. This is checked in addition to the query's
+    /// [`FunctionKind`].
+    pub is_generator: Option<bool>,
+}
+
+impl FunctionSignature {
+    /// Creates a signature matching functions with exactly these parameter names, such as
+    /// `["url", "opts"]`.
+    #[must_use]
+    pub fn params(names: &[&str]) -> Self {
+        FunctionSignature {
+            param_count: Some(names.len()),
+            param_names: Some(names.iter().map(ToString::to_string).collect()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn matches(
+        &self,
+        params: &[ParamShape],
+        is_async: bool,
+        is_generator: bool,
+    ) -> bool {
+        self.param_count.is_none_or(|count| count == params.len())
+            && self.param_names.as_ref().is_none_or(|names| {
+                names.len() == params.len()
+                    && names
+                        .iter()
+                        .zip(params)
+                        .all(|(name, param)| name_matches(name, &param.name))
+            })
+            && self
+                .has_rest
+                .is_none_or(|rest| rest == params.last().is_some_and(|p| p.is_rest))
+            && self
+                .has_default
+                .is_none_or(|default| default == params.iter().any(|p| p.has_default))
+            && self.is_async.is_none_or(|flag| flag == is_async)
+            && self.is_generator.is_none_or(|flag| flag == is_generator)
+    }
+}
+
+/// Describes a decorator a method has to be decorated with for a [`FunctionQuery`] to match it,
+/// such as `@Get()` on a route handler.
+#[derive(Debug, Clone)]
+pub struct DecoratorQuery {
+    /// The name of the decorator, which is the dotted path of the expression it's called with,
+    /// like `Get` or `nest.Get`. This can be a pattern, like the name fields of
+    /// [`FunctionQuery`].
+    pub name: String,
+    /// The leading arguments the decorator has to be called with, if any. These are compared
+    /// with the values of literal arguments as they are, rather than as patterns, so `/users`
+    /// matches `@Get('/users')`, and `/users/` only matches `@Get('/users/')`. Other arguments,
+    /// such as object literals, are compared as an empty string. Decorators called with more
+    /// arguments than these still match.
+    pub args: Option<Vec<String>>,
+}
+
+impl DecoratorQuery {
+    /// Creates a query matching decorators with the given name, whatever they're called with.
+    #[must_use]
+    pub fn named(name: &str) -> Self {
+        DecoratorQuery {
+            name: name.to_string(),
+            args: None,
+        }
+    }
+
+    /// Restricts this query to decorators called with the given leading arguments.
+    #[must_use]
+    pub fn with_args(mut self, args: &[&str]) -> Self {
+        self.args = Some(args.iter().map(ToString::to_string).collect());
+        self
+    }
+
+    pub(crate) fn matches(&self, decorators: &[Decorator]) -> bool {
+        decorators.iter().any(|decorator| {
+            let (callee, args) = match &*decorator.expr {
+                Expr::Call(call) => match &call.callee {
+                    Callee::Expr(callee) => (&**callee, Some(&call.args)),
+                    _ => return false,
+                },
+                expr => (expr, None),
+            };
+            let Some((root, mut segments)) = member_segments(callee) else {
+                return false;
+            };
+            segments.insert(0, root.sym.to_string());
+            name_matches(&self.name, &segments.join("."))
+                && self.args.as_ref().is_none_or(|expected| {
+                    let args = args.map(Vec::as_slice).unwrap_or_default();
+                    expected.len() <= args.len()
+                        && expected
+                            .iter()
+                            .zip(args)
+                            .all(|(expected, arg)| *expected == decorator_arg(arg))
+                })
+        })
+    }
+}
+
+/// The value of a decorator argument as matched by [`DecoratorQuery::args`].
+fn decorator_arg(arg: &ExprOrSpread) -> String {
+    if arg.spread.is_some() {
+        return String::new();
+    }
+    match &*arg.expr {
+        Expr::Lit(Lit::Str(str_lit)) => str_lit.value.to_string(),
+        Expr::Lit(Lit::Num(num)) => num.value.to_string(),
+        Expr::Lit(Lit::Bool(bool_lit)) => bool_lit.value.to_string(),
+        Expr::Lit(Lit::Null(_)) => "null".to_string(),
+        Expr::Ident(ident) => ident.sym.to_string(),
+        Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl
+            .quasis
+            .iter()
+            .filter_map(|quasi| quasi.cooked.as_ref())
+            .map(ToString::to_string)
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+/// A range of positions in a source file. Lines and columns both start at 1, and columns count
+/// characters rather than bytes. Both ends are inclusive.
+#[derive(Debug, Clone)]
+pub struct SourceRange {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl SourceRange {
+    #[must_use]
+    pub fn new(start_line: usize, start_column: usize, end_line: usize, end_column: usize) -> Self {
+        SourceRange {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
+
+    /// Creates a range covering a single position.
+    #[must_use]
+    pub fn at(line: usize, column: usize) -> Self {
+        SourceRange::new(line, column, line, column)
+    }
+
+    #[must_use]
+    pub fn contains(&self, line: usize, column: usize) -> bool {
+        (self.start_line, self.start_column) <= (line, column)
+            && (line, column) <= (self.end_line, self.end_column)
+    }
+}
+
+/// Describes which functions in a file an instrumentation applies to. When several functions
+/// match, `index` selects one of them, counting from zero in source order.
+///
+/// The class, method and function name fields can be patterns rather than exact names. A name
+/// wrapped in slashes, like `/^(get|set|del)$/`, is a regular expression, and a name containing
+/// `*` or `?` is a wildcard pattern, where `*` matches any number of characters and `?` matches
+/// exactly one. For example, `/^[^_]/` matches every name that doesn't start with an underscore.
+/// Each distinct name matched by a pattern is counted separately for `index`, and channel names
+/// can include the matched names; see [`InstrumentationConfig::channel_name`].
+///
+/// Queries also have a short textual form, such as `Undici#fetch:async[2]`, which they can be
+/// parsed from and are displayed as.
+///
+/// [`InstrumentationConfig::channel_name`]: crate::InstrumentationConfig::channel_name
 #[derive(Debug, Clone)]
 pub enum FunctionQuery {
     ClassConstructor {
@@ -67,6 +491,90 @@ pub enum FunctionQuery {
         kind: FunctionKind,
         index: usize,
     },
+    /// Matches a plain function that's used as a constructor, as in
+    /// `function Connection(options) { ... }` called with `new Connection(options)`, whether it's
+    /// declared or assigned to a variable or property. Like a [`FunctionQuery::ClassConstructor`],
+    /// it publishes the constructed object as `self` once the function has run, but only when
+    /// it's called with `new`, as told by `new.target`. Plain calls, such as those made by
+    /// subclasses through `Connection.call(this, options)`, or those that call `new` themselves
+    /// when `this` isn't an instance, aren't traced, so nothing is published twice.
+    FunctionConstructor {
+        function_name: String,
+        index: usize,
+    },
+    /// Matches a function by the path through which it's reachable from the module's exports,
+    /// rather than by its local name. The first segment is the exported name, and any further
+    /// segments are properties of it, for example `fetch`, `Undici2.fetch` or
+    /// `Client.prototype.query`. Instance methods of exported classes can be reached either
+    /// directly through the class or through its `prototype`. Local aliases, such as
+    /// `export { a as fetch }` or `exports.Undici2 = Undici`, are resolved within the file, and
+    /// anything other than an object literal assigned to `module.exports` is exported as
+    /// `default`.
+    Exported {
+        export_path: String,
+        kind: FunctionKind,
+        index: usize,
+    },
+    /// Matches a function by where it starts in the source, which is the `function` keyword, or
+    /// `async` for async functions, and the name for methods. This is meant for bundled or
+    /// minified code, where names are meaningless, so the module's version range should pin the
+    /// exact build the positions were taken from. Positions are only known when the
+    /// [`InstrumentationVisitor`] has been given a source map; otherwise nothing matches.
+    ///
+    /// [`InstrumentationVisitor`]: crate::InstrumentationVisitor
+    Location {
+        range: SourceRange,
+        kind: FunctionKind,
+        index: usize,
+    },
+    /// Matches a function by the structural fingerprint of its body, which ignores the names of
+    /// variables and parameters, so it survives minification. Fingerprints can be computed from
+    /// a readable build with [`function_fingerprints`]. Class constructors can't be matched this
+    /// way.
+    ///
+    /// [`function_fingerprints`]: crate::function_fingerprints
+    Fingerprint {
+        fingerprint: String,
+        kind: FunctionKind,
+        index: usize,
+    },
+    /// Matches the functions found by a [`Selector`], such as
+    /// `Class[name=Undici] > Method[name=fetch]`, which can describe a function's surroundings
+    /// in more detail than the other variants.
+    Selector {
+        selector: Selector,
+        kind: FunctionKind,
+        index: usize,
+    },
+    /// Matches what the first of `alternatives` to match anything in a file matches, which
+    /// keeps instrumentations working when a function is renamed between versions, e.g. from
+    /// `_doRequest` to `dispatch`. The alternative applied to each file is recorded by the
+    /// [`InstrumentationVisitor`]. This has to be the outermost query.
+    ///
+    /// [`InstrumentationVisitor`]: crate::InstrumentationVisitor
+    FirstOf {
+        alternatives: Vec<FunctionQuery>,
+    },
+    /// Matches what `query` matches, but only within the given [`FunctionScope`]. The `index` of
+    /// `query` only counts functions within the scope.
+    Scoped {
+        query: Box<FunctionQuery>,
+        scope: FunctionScope,
+    },
+    /// Matches what `query` matches, but only functions with the given [`FunctionSignature`].
+    /// The `index` of `query` only counts functions with the signature.
+    Signature {
+        query: Box<FunctionQuery>,
+        signature: FunctionSignature,
+    },
+    /// Matches what `query` matches, but only methods decorated with a decorator matching the
+    /// given [`DecoratorQuery`]. The `index` of `query` only counts methods with the decorator.
+    /// Tracing leaves the decorators and parameters of a method as they are, so decorators that
+    /// wrap its descriptor or record metadata about it keep working.
+    Decorated {
+        query: Box<FunctionQuery>,
+        decorator: DecoratorQuery,
+    },
 }
 
 impl FunctionQuery {
@@ -115,13 +623,98 @@ impl FunctionQuery {
         }
     }
 
+    #[must_use]
+    pub fn function_constructor(function_name: &str) -> Self {
+        FunctionQuery::FunctionConstructor {
+            function_name: function_name.to_string(),
+            index: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn exported(export_path: &str, kind: FunctionKind) -> Self {
+        FunctionQuery::Exported {
+            export_path: export_path.to_string(),
+            kind,
+            index: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn location(range: SourceRange, kind: FunctionKind) -> Self {
+        FunctionQuery::Location {
+            range,
+            kind,
+            index: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn fingerprint(fingerprint: &str, kind: FunctionKind) -> Self {
+        FunctionQuery::Fingerprint {
+            fingerprint: fingerprint.to_string(),
+            kind,
+            index: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn selector(selector: Selector, kind: FunctionKind) -> Self {
+        FunctionQuery::Selector {
+            selector,
+            kind,
+            index: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn first_of(alternatives: Vec<FunctionQuery>) -> Self {
+        FunctionQuery::FirstOf { alternatives }
+    }
+
+    /// Restricts this query to the given [`FunctionScope`].
+    #[must_use]
+    pub fn in_scope(self, scope: FunctionScope) -> Self {
+        FunctionQuery::Scoped {
+            query: Box::new(self),
+            scope,
+        }
+    }
+
+    /// Restricts this query to functions with the given [`FunctionSignature`].
+    #[must_use]
+    pub fn with_signature(self, signature: FunctionSignature) -> Self {
+        FunctionQuery::Signature {
+            query: Box::new(self),
+            signature,
+        }
+    }
+
+    /// Restricts this query to methods with a decorator matching the given [`DecoratorQuery`].
+    #[must_use]
+    pub fn with_decorator(self, decorator: DecoratorQuery) -> Self {
+        FunctionQuery::Decorated {
+            query: Box::new(self),
+            decorator,
+        }
+    }
+
     pub(crate) fn kind(&self) -> &FunctionKind {
         match self {
-            FunctionQuery::ClassConstructor { .. } => &FunctionKind::Sync,
+            FunctionQuery::ClassConstructor { .. }
+            | FunctionQuery::FunctionConstructor { .. }
+            | FunctionQuery::FirstOf { .. } => &FunctionKind::Sync,
             FunctionQuery::ClassMethod { kind, .. }
             | FunctionQuery::ObjectMethod { kind, .. }
             | FunctionQuery::FunctionDeclaration { kind, .. }
-            | FunctionQuery::FunctionExpression { kind, .. } => kind,
+            | FunctionQuery::FunctionExpression { kind, .. }
+            | FunctionQuery::Exported { kind, .. }
+            | FunctionQuery::Location { kind, .. }
+            | FunctionQuery::Fingerprint { kind, .. }
+            | FunctionQuery::Selector { kind, .. } => kind,
+            FunctionQuery::Scoped { query, .. }
+            | FunctionQuery::Signature { query, .. }
+            | FunctionQuery::Decorated { query, .. } => query.kind(),
         }
     }
 
@@ -130,10 +723,18 @@ impl FunctionQuery {
             FunctionQuery::ClassConstructor { .. } => "constructor",
             FunctionQuery::ClassMethod { method_name, .. }
             | FunctionQuery::ObjectMethod { method_name, .. } => method_name,
-            FunctionQuery::FunctionDeclaration { function_name, .. } => function_name,
+            FunctionQuery::FunctionDeclaration { function_name, .. }
+            | FunctionQuery::FunctionConstructor { function_name, .. } => function_name,
             FunctionQuery::FunctionExpression {
                 expression_name, ..
             } => expression_name,
+            FunctionQuery::Exported { export_path, .. } => export_path,
+            FunctionQuery::Location { .. } | FunctionQuery::FirstOf { .. } => "",
+            FunctionQuery::Fingerprint { fingerprint, .. } => fingerprint,
+            FunctionQuery::Selector { selector, .. } => selector.as_str(),
+            FunctionQuery::Scoped { query, .. }
+            | FunctionQuery::Signature { query, .. }
+            | FunctionQuery::Decorated { query, .. } => query.name(),
         }
     }
 
@@ -144,6 +745,15 @@ impl FunctionQuery {
             | FunctionQuery::ObjectMethod { .. } => FunctionType::Method,
             FunctionQuery::FunctionDeclaration { .. } => FunctionType::FunctionDeclaration,
             FunctionQuery::FunctionExpression { .. } => FunctionType::FunctionExpression,
+            FunctionQuery::FunctionConstructor { .. } => FunctionType::ConstructorFunction,
+            FunctionQuery::Exported { .. } => FunctionType::Export,
+            FunctionQuery::Location { .. } => FunctionType::Location,
+            FunctionQuery::Fingerprint { .. } => FunctionType::Fingerprint,
+            FunctionQuery::Selector { .. } => FunctionType::Selector,
+            FunctionQuery::FirstOf { .. } => FunctionType::Alternatives,
+            FunctionQuery::Scoped { query, .. }
+            | FunctionQuery::Signature { query, .. }
+            | FunctionQuery::Decorated { query, .. } => query.typ(),
         }
     }
 
@@ -154,7 +764,16 @@ impl FunctionQuery {
             | FunctionQuery::ClassMethod { index, .. }
             | FunctionQuery::ObjectMethod { index, .. }
             | FunctionQuery::FunctionDeclaration { index, .. }
-            | FunctionQuery::FunctionExpression { index, .. } => *index,
+            | FunctionQuery::FunctionExpression { index, .. }
+            | FunctionQuery::FunctionConstructor { index, .. }
+            | FunctionQuery::Exported { index, .. }
+            | FunctionQuery::Location { index, .. }
+            | FunctionQuery::Fingerprint { index, .. }
+            | FunctionQuery::Selector { index, .. } => *index,
+            FunctionQuery::FirstOf { .. } => 0,
+            FunctionQuery::Scoped { query, .. }
+            | FunctionQuery::Signature { query, .. }
+            | FunctionQuery::Decorated { query, .. } => query.index(),
         }
     }
 
@@ -163,41 +782,858 @@ impl FunctionQuery {
         match self {
             FunctionQuery::ClassConstructor { class_name, .. }
             | FunctionQuery::ClassMethod { class_name, .. } => Some(class_name),
+            FunctionQuery::Scoped { query, .. }
+            | FunctionQuery::Signature { query, .. }
+            | FunctionQuery::Decorated { query, .. } => query.class_name(),
             _ => None,
         }
     }
 
-    fn maybe_increment_count(&self, matches_except_count: bool, count: &mut usize) -> bool {
-        if matches_except_count {
-            if self.index() == *count {
-                true
-            } else {
-                *count += 1;
-                false
+    /// Checks that every name pattern in this query is valid.
+    /// # Errors
+    /// Returns an error if a regular expression can't be compiled.
+    pub fn validate(&self) -> Result<(), OrchestrionError> {
+        let names = match self {
+            FunctionQuery::ClassMethod {
+                class_name,
+                method_name,
+                ..
+            } => vec![class_name, method_name],
+            FunctionQuery::ClassConstructor { class_name, .. } => vec![class_name],
+            FunctionQuery::ObjectMethod { method_name, .. } => vec![method_name],
+            FunctionQuery::FunctionDeclaration { function_name, .. }
+            | FunctionQuery::FunctionConstructor { function_name, .. } => vec![function_name],
+            FunctionQuery::FunctionExpression {
+                expression_name, ..
+            } => vec![expression_name],
+            FunctionQuery::Exported { .. }
+            | FunctionQuery::Location { .. }
+            | FunctionQuery::Fingerprint { .. }
+            | FunctionQuery::Selector { .. } => vec![],
+            FunctionQuery::FirstOf { alternatives } => {
+                for alternative in alternatives {
+                    if alternative.alternatives().is_some() {
+                        return Err(
+                            format!("Alternatives must be the outermost query, in {self}").into(),
+                        );
+                    }
+                    alternative.validate()?;
+                }
+                vec![]
+            }
+            FunctionQuery::Scoped { query, .. } => {
+                if query.alternatives().is_some() {
+                    return Err(
+                        format!("Alternatives must be the outermost query, in {self}").into(),
+                    );
+                }
+                return query.validate();
+            }
+            FunctionQuery::Signature { query, signature } => {
+                if query.alternatives().is_some() {
+                    return Err(
+                        format!("Alternatives must be the outermost query, in {self}").into(),
+                    );
+                }
+                query.validate()?;
+                signature.param_names.iter().flatten().collect()
+            }
+            FunctionQuery::Decorated { query, decorator } => {
+                if query.alternatives().is_some() {
+                    return Err(
+                        format!("Alternatives must be the outermost query, in {self}").into(),
+                    );
+                }
+                query.validate()?;
+                vec![&decorator.name]
+            }
+        };
+        for name in names {
+            validate_name(name)?;
+        }
+        Ok(())
+    }
+
+    /// Whether this query can match constructors, which are traced differently from other
+    /// functions.
+    pub(crate) fn traces_constructors(&self) -> bool {
+        match self {
+            FunctionQuery::FirstOf { alternatives } => {
+                alternatives.iter().any(FunctionQuery::traces_constructors)
+            }
+            _ => {
+                matches!(self.typ(), FunctionType::ConstructorFunction)
+                    || self.name() == "constructor"
+            }
+        }
+    }
+
+    /// Whether matching this query needs an analysis of the whole program.
+    pub(crate) fn needs_analysis(&self) -> bool {
+        match self {
+            FunctionQuery::Exported { .. }
+            | FunctionQuery::Location { .. }
+            | FunctionQuery::Fingerprint { .. }
+            | FunctionQuery::Selector { .. }
+            | FunctionQuery::Scoped { .. } => true,
+            FunctionQuery::Signature { query, .. } | FunctionQuery::Decorated { query, .. } => {
+                query.needs_analysis()
+            }
+            FunctionQuery::FirstOf { alternatives } => {
+                alternatives.iter().any(FunctionQuery::needs_analysis)
+            }
+            _ => false,
+        }
+    }
+
+    /// The selectors used by this query, which have to be matched during the analysis.
+    pub(crate) fn selectors(&self) -> Vec<&Selector> {
+        match self {
+            FunctionQuery::Selector { selector, .. } => vec![selector],
+            FunctionQuery::Scoped { query, .. }
+            | FunctionQuery::Signature { query, .. }
+            | FunctionQuery::Decorated { query, .. } => query.selectors(),
+            FunctionQuery::FirstOf { alternatives } => alternatives
+                .iter()
+                .flat_map(FunctionQuery::selectors)
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// The alternatives to choose from for each file, if this query has any.
+    pub(crate) fn alternatives(&self) -> Option<&[FunctionQuery]> {
+        match self {
+            FunctionQuery::FirstOf { alternatives } => Some(alternatives),
+            _ => None,
+        }
+    }
+
+    /// Whether matching this query needs the fingerprints of every function in the program.
+    pub(crate) fn needs_fingerprints(&self) -> bool {
+        match self {
+            FunctionQuery::Fingerprint { .. } => true,
+            FunctionQuery::Scoped { query, .. }
+            | FunctionQuery::Signature { query, .. }
+            | FunctionQuery::Decorated { query, .. } => query.needs_fingerprints(),
+            FunctionQuery::FirstOf { alternatives } => {
+                alternatives.iter().any(FunctionQuery::needs_fingerprints)
             }
-        } else {
-            false
+            _ => false,
         }
     }
 
+    /// Checks the scope restrictions of this query against a function's analysis. Functions
+    /// that weren't analyzed are only in scope for unscoped queries.
+    pub(crate) fn matches_scope(&self, info: Option<&FunctionInfo>) -> bool {
+        match self {
+            FunctionQuery::Scoped { query, scope } => {
+                info.is_some_and(|info| scope.matches(info)) && query.matches_scope(info)
+            }
+            FunctionQuery::Signature { query, .. } | FunctionQuery::Decorated { query, .. } => {
+                query.matches_scope(info)
+            }
+            _ => true,
+        }
+    }
+
+    /// Checks the signature restrictions of this query against a function's parameters.
+    pub(crate) fn matches_signature(
+        &self,
+        params: &[ParamShape],
+        is_async: bool,
+        is_generator: bool,
+    ) -> bool {
+        match self {
+            FunctionQuery::Signature { query, signature } => {
+                signature.matches(params, is_async, is_generator)
+                    && query.matches_signature(params, is_async, is_generator)
+            }
+            FunctionQuery::Scoped { query, .. } | FunctionQuery::Decorated { query, .. } => {
+                query.matches_signature(params, is_async, is_generator)
+            }
+            _ => true,
+        }
+    }
+
+    /// Checks the decorator restrictions of this query against a method's decorators.
+    pub(crate) fn matches_decorators(&self, decorators: &[Decorator]) -> bool {
+        match self {
+            FunctionQuery::Decorated { query, decorator } => {
+                decorator.matches(decorators) && query.matches_decorators(decorators)
+            }
+            FunctionQuery::Scoped { query, .. } | FunctionQuery::Signature { query, .. } => {
+                query.matches_decorators(decorators)
+            }
+            _ => true,
+        }
+    }
+
+    fn matches_function_signature(&self, func: &Function) -> bool {
+        self.matches_signature(
+            &ParamShape::of_function(func),
+            func.is_async,
+            func.is_generator,
+        )
+    }
+
+    /// Counts a function that matches apart from its `index`, returning whether it's the one at
+    /// `index`. The count is only kept here, so it's always under the key it was checked with.
+    fn maybe_increment_count(&self, matches_except_count: bool, count: &mut usize) -> bool {
+        if !matches_except_count {
+            return false;
+        }
+        let is_at_index = self.index() == *count;
+        *count += 1;
+        is_at_index
+    }
+
+    // Functions without a body, such as TypeScript overload signatures and abstract or `declare`
+    // methods, are never matched, so they don't count towards `index` either.
+
     pub fn matches_decl(&self, func: &FnDecl, count: &mut usize) -> bool {
-        let matches_except_count = matches!(self.typ(), FunctionType::FunctionDeclaration)
+        let matches_except_count = func.function.body.is_some()
+            && matches!(self.typ(), FunctionType::FunctionDeclaration)
             && self.kind().matches(&func.function)
-            && func.ident.sym == self.name();
+            && name_matches(self.name(), &func.ident.sym)
+            && self.matches_function_signature(&func.function)
+            && self.matches_decorators(&func.function.decorators);
         self.maybe_increment_count(matches_except_count, count)
     }
 
     pub fn matches_expr(&self, func: &FnExpr, count: &mut usize, name: &str) -> bool {
-        let matches_except_count = matches!(self.typ(), FunctionType::FunctionExpression)
+        let matches_except_count = func.function.body.is_some()
+            && matches!(self.typ(), FunctionType::FunctionExpression)
             && self.kind().matches(&func.function)
-            && name == self.name();
+            && name_matches(self.name(), name)
+            && self.matches_function_signature(&func.function)
+            && self.matches_decorators(&func.function.decorators);
         self.maybe_increment_count(matches_except_count, count)
     }
 
     pub fn matches_method(&self, func: &Function, count: &mut usize, name: &str) -> bool {
-        let matches_except_count = matches!(self.typ(), FunctionType::Method)
+        let matches_except_count = func.body.is_some()
+            && matches!(self.typ(), FunctionType::Method)
             && self.kind().matches(func)
-            && name == self.name();
+            && name_matches(self.name(), name)
+            && self.matches_function_signature(func)
+            && self.matches_decorators(&func.decorators);
+        self.maybe_increment_count(matches_except_count, count)
+    }
+
+    /// Matches a plain function used as a constructor, which can't be async or a generator, by
+    /// the name it's declared or assigned with.
+    pub fn matches_constructor_function(
+        &self,
+        func: &Function,
+        count: &mut usize,
+        name: &str,
+    ) -> bool {
+        let matches_except_count = func.body.is_some()
+            && matches!(self.typ(), FunctionType::ConstructorFunction)
+            && !func.is_async
+            && !func.is_generator
+            && name_matches(self.name(), name)
+            && self.matches_function_signature(func)
+            && self.matches_decorators(&func.decorators);
         self.maybe_increment_count(matches_except_count, count)
     }
+
+    pub fn matches_export(
+        &self,
+        func: &Function,
+        count: &mut usize,
+        export_paths: &[String],
+    ) -> bool {
+        let info = FunctionInfo {
+            export_paths: export_paths.to_vec(),
+            ..Default::default()
+        };
+        self.matches_analyzed(func, count, Some(&info))
+    }
+
+    /// Matches queries that find functions through the analysis of the whole program, rather
+    /// than through their surroundings.
+    pub(crate) fn matches_analyzed(
+        &self,
+        func: &Function,
+        count: &mut usize,
+        info: Option<&FunctionInfo>,
+    ) -> bool {
+        let matches_analysis = info.is_some_and(|info| match self.typ() {
+            FunctionType::Export => info.export_paths.iter().any(|path| path == self.name()),
+            FunctionType::Location => info.position.is_some_and(|(line, column)| {
+                self.range().is_some_and(|r| r.contains(line, column))
+            }),
+            FunctionType::Fingerprint => info.fingerprint.as_deref() == Some(self.name()),
+            FunctionType::Selector => info.selectors.iter().any(|s| s == self.name()),
+            _ => false,
+        });
+        let matches_except_count = func.body.is_some()
+            && matches_analysis
+            && self.kind().matches(func)
+            && self.matches_function_signature(func)
+            && self.matches_decorators(&func.decorators);
+        self.maybe_increment_count(matches_except_count, count)
+    }
+
+    fn range(&self) -> Option<&SourceRange> {
+        match self {
+            FunctionQuery::Location { range, .. } => Some(range),
+            FunctionQuery::Scoped { query, .. }
+            | FunctionQuery::Signature { query, .. }
+            | FunctionQuery::Decorated { query, .. } => query.range(),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FunctionKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            FunctionKind::Sync => "sync",
+            FunctionKind::Async => "async",
+        })
+    }
+}
+
+impl FromStr for FunctionKind {
+    type Err = OrchestrionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "sync" => Ok(FunctionKind::Sync),
+            "async" => Ok(FunctionKind::Async),
+            other => Err(format!("Invalid function kind {other:?}, expected sync or async").into()),
+        }
+    }
+}
+
+/// Written as `line:column`, or `line:column-line:column` for a range of more than one position.
+impl Display for SourceRange {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.start_line, self.start_column)?;
+        if (self.start_line, self.start_column) != (self.end_line, self.end_column) {
+            write!(f, "-{}:{}", self.end_line, self.end_column)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SourceRange {
+    type Err = OrchestrionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let position = |s: &str| -> Option<(usize, usize)> {
+            let (line, column) = s.trim().split_once(':')?;
+            Some((line.parse().ok()?, column.parse().ok()?))
+        };
+        let (start, end) = s.split_once('-').unwrap_or((s, s));
+        match (position(start), position(end)) {
+            (Some((start_line, start_column)), Some((end_line, end_column))) => Ok(
+                SourceRange::new(start_line, start_column, end_line, end_column),
+            ),
+            _ => Err(format!(
+                "Invalid source range {s:?}, expected line:column or line:column-line:column"
+            )
+            .into()),
+        }
+    }
+}
+
+/// Written as `@Get`, or `@Get(/users, 1)` when arguments are given.
+impl Display for DecoratorQuery {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "@{}", quote(&self.name, CLAUSE_SYNTAX))?;
+        if let Some(args) = &self.args {
+            let args: Vec<_> = args.iter().map(|arg| quote(arg, CLAUSE_SYNTAX)).collect();
+            write!(f, "({})", args.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl FunctionScope {
+    fn clauses(&self) -> Vec<String> {
+        let mut clauses = vec![];
+        if self.top_level {
+            clauses.push("top-level".to_string());
+        }
+        if !self.enclosing.is_empty() {
+            let enclosing: Vec<_> = self
+                .enclosing
+                .iter()
+                .map(|name| quote(name, CLAUSE_SYNTAX))
+                .collect();
+            clauses.push(format!("within {}", enclosing.join(" > ")));
+        }
+        if self.unshadowed {
+            clauses.push("unshadowed".to_string());
+        }
+        clauses
+    }
+}
+
+impl FunctionSignature {
+    fn clauses(&self) -> Vec<String> {
+        let mut clauses = vec![];
+        if let Some(names) = &self.param_names {
+            let names: Vec<_> = names
+                .iter()
+                .map(|name| quote(name, CLAUSE_SYNTAX))
+                .collect();
+            clauses.push(format!("params({})", names.join(", ")));
+        }
+        if let Some(count) = self.param_count {
+            if self
+                .param_names
+                .as_ref()
+                .is_none_or(|names| names.len() != count)
+            {
+                clauses.push(format!("params={count}"));
+            }
+        }
+        for (flag, value) in [
+            ("rest", self.has_rest),
+            ("default", self.has_default),
+            ("async", self.is_async),
+            ("generator", self.is_generator),
+        ] {
+            match value {
+                Some(true) => clauses.push(flag.to_string()),
+                Some(false) => clauses.push(format!("!{flag}")),
+                None => {}
+            }
+        }
+        clauses
+    }
+}
+
+/// The characters that mark the end of a name within a `where` clause, as in `params(a, b)` or
+/// `within a > b`, besides those [`quote`] always quotes names for.
+const CLAUSE_SYNTAX: &[char] = &[',', '(', ')', '>'];
+
+/// Writes a name in the short form of a query, in double quotes if it could otherwise be taken
+/// for the syntax around it, such as a pattern containing ` | ` or a name ending with `()`.
+/// Backslashes and double quotes within quotes are escaped with a backslash. `syntax` lists the
+/// other characters that can't be written as they are where the name is written.
+fn quote<'a>(name: &'a str, syntax: &[char]) -> Cow<'a, str> {
+    let ambiguous = name.is_empty()
+        || name.ends_with(']')
+        || name.ends_with("()")
+        || name.starts_with("{}.")
+        || name
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | ':') || syntax.contains(&c));
+    if ambiguous {
+        Cow::Owned(format!(
+            "\"{}\"",
+            name.replace('\\', "\\\\").replace('"', "\\\"")
+        ))
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+/// Reads a name written by [`quote`], which is only unescaped if it's in double quotes.
+fn unquote(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    let Some(quoted) = name.strip_prefix('"') else {
+        return Ok(name.to_string());
+    };
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.extend(chars.next()),
+            '"' if chars.as_str().is_empty() => return Ok(value),
+            '"' => return Err(format!("unexpected {:?} after {name:?}", chars.as_str())),
+            c => value.push(c),
+        }
+    }
+    Err(format!("unterminated quotes in {name:?}"))
+}
+
+/// Finds the first `separator` in the short form of a query that isn't within quotes, whether
+/// those of a quoted name or of a selector's attribute, or within parentheses.
+fn find_unquoted(input: &str, separator: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        if let Some(open) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == open {
+                quote = None;
+            }
+            continue;
+        }
+        if depth == 0 && input[i..].starts_with(separator) {
+            return Some(i);
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits the short form of a query on each `separator` found by [`find_unquoted`].
+fn split_unquoted<'a>(mut input: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = vec![];
+    while let Some(i) = find_unquoted(input, separator) {
+        parts.push(&input[..i]);
+        input = &input[i + separator.len()..];
+    }
+    parts.push(input);
+    parts
+}
+
+/// Reads the names of a list in a `where` clause, as in `params(a, b)`, leaving out empty ones.
+fn unquote_list(list: &str, separator: &str) -> Result<Vec<String>, String> {
+    split_unquoted(list, separator)
+        .into_iter()
+        .filter(|name| !name.trim().is_empty())
+        .map(unquote)
+        .collect()
+}
+
+/// Splits the index and kind off the end of a query in its short form, as in
+/// `Undici#fetch:async[2]`.
+fn split_suffixes(mut base: &str) -> (&str, usize, Option<FunctionKind>) {
+    let mut index = 0;
+    if let Some((rest, digits)) = base.strip_suffix(']').and_then(|b| b.rsplit_once('[')) {
+        if let Ok(parsed) = digits.parse() {
+            index = parsed;
+            base = rest;
+        }
+    }
+    let mut kind = None;
+    if let Some((rest, parsed)) = base.rsplit_once(':') {
+        if let Ok(parsed) = parsed.parse::<FunctionKind>() {
+            kind = Some(parsed);
+            base = rest;
+        }
+    }
+    (base, index, kind)
+}
+
+/// Parses the part of the short form of a query before its `where` clauses, reporting errors
+/// against the whole `input`.
+fn parse_base(input: &str, base: &str) -> Result<FunctionQuery, OrchestrionError> {
+    let invalid = |reason: &str| -> OrchestrionError {
+        format!("Invalid function query {input:?}: {reason}").into()
+    };
+    let name = |name: &str| -> Result<String, OrchestrionError> {
+        let name = unquote(name).map_err(|reason| invalid(&reason))?;
+        if name.is_empty() {
+            return Err(invalid("missing a name"));
+        }
+        Ok(name)
+    };
+
+    let (base, index, parsed_kind) = split_suffixes(base.trim());
+    let kind = parsed_kind.clone().unwrap_or(FunctionKind::Sync);
+
+    let query = if let Some(class_name) = base.strip_prefix("new ") {
+        if parsed_kind.is_some() {
+            return Err(invalid("constructors don't have a kind"));
+        }
+        match class_name.strip_suffix("()") {
+            Some(function_name) => FunctionQuery::FunctionConstructor {
+                function_name: name(function_name)?,
+                index,
+            },
+            None => FunctionQuery::ClassConstructor {
+                class_name: name(class_name)?,
+                index,
+            },
+        }
+    } else if let Some(export_path) = base.strip_prefix("export ") {
+        FunctionQuery::Exported {
+            export_path: name(export_path)?,
+            kind,
+            index,
+        }
+    } else if let Some(range) = base.strip_prefix("at ") {
+        FunctionQuery::Location {
+            range: range.parse()?,
+            kind,
+            index,
+        }
+    } else if let Some(fingerprint) = base.strip_prefix("fingerprint ") {
+        FunctionQuery::Fingerprint {
+            fingerprint: name(fingerprint)?,
+            kind,
+            index,
+        }
+    } else if let Some(selector) = base.strip_prefix("select ") {
+        FunctionQuery::Selector {
+            selector: selector.parse()?,
+            kind,
+            index,
+        }
+    } else if let Some(method_name) = base.strip_prefix("{}.") {
+        FunctionQuery::ObjectMethod {
+            method_name: name(method_name)?,
+            kind,
+            index,
+        }
+    } else if let Some(expression_name) = base.strip_suffix(" = function()") {
+        FunctionQuery::FunctionExpression {
+            expression_name: name(expression_name)?,
+            kind,
+            index,
+        }
+    } else if let Some(function_name) = base.strip_suffix("()") {
+        FunctionQuery::FunctionDeclaration {
+            function_name: name(function_name)?,
+            kind,
+            index,
+        }
+    } else if let Some(i) = find_unquoted(base, "#") {
+        FunctionQuery::ClassMethod {
+            class_name: name(&base[..i])?,
+            method_name: name(&base[i + 1..])?,
+            kind,
+            index,
+        }
+    } else {
+        return Err(invalid(
+            "expected a form like `Class#method:async`, `name():sync`, `{}.method:sync`, \
+             `name = function():sync`, `new Class` or `new Function()`",
+        ));
+    };
+    Ok(query)
+}
+
+/// Applies the `where` clauses of a query in its short form.
+fn restrict(mut query: FunctionQuery, clauses: &str) -> Result<FunctionQuery, String> {
+    let mut scope: Option<FunctionScope> = None;
+    let mut signature: Option<FunctionSignature> = None;
+    let mut decorators = vec![];
+    for clause in split_unquoted(clauses, ",") {
+        let clause = clause.trim();
+        let (flag, value) = match clause.strip_prefix('!') {
+            Some(flag) => (flag, false),
+            None => (clause, true),
+        };
+        match flag {
+            "top-level" if value => scope.get_or_insert_default().top_level = true,
+            "unshadowed" if value => scope.get_or_insert_default().unshadowed = true,
+            "rest" => signature.get_or_insert_default().has_rest = Some(value),
+            "default" => signature.get_or_insert_default().has_default = Some(value),
+            "async" => signature.get_or_insert_default().is_async = Some(value),
+            "generator" => signature.get_or_insert_default().is_generator = Some(value),
+            _ => {
+                if let Some(decorator) = clause.strip_prefix('@') {
+                    decorators.push(match find_unquoted(decorator, "(") {
+                        Some(i) => DecoratorQuery {
+                            name: unquote(&decorator[..i])?,
+                            args: Some(unquote_list(
+                                decorator[i + 1..]
+                                    .strip_suffix(')')
+                                    .ok_or_else(|| format!("unclosed decorator {clause:?}"))?,
+                                ",",
+                            )?),
+                        },
+                        None => DecoratorQuery::named(&unquote(decorator)?),
+                    });
+                } else if let Some(path) = clause.strip_prefix("within ") {
+                    scope.get_or_insert_default().enclosing = unquote_list(path, ">")?;
+                } else if let Some(params) = clause
+                    .strip_prefix("params(")
+                    .and_then(|params| params.strip_suffix(')'))
+                {
+                    let names = unquote_list(params, ",")?;
+                    let signature = signature.get_or_insert_default();
+                    signature.param_count = Some(names.len());
+                    signature.param_names = Some(names);
+                } else if let Some(count) = clause.strip_prefix("params=") {
+                    signature.get_or_insert_default().param_count = Some(
+                        count
+                            .trim()
+                            .parse()
+                            .map_err(|_| format!("invalid parameter count {count:?}"))?,
+                    );
+                } else {
+                    return Err(format!("unknown clause {clause:?}"));
+                }
+            }
+        }
+    }
+    if let Some(scope) = scope {
+        query = query.in_scope(scope);
+    }
+    if let Some(signature) = signature {
+        query = query.with_signature(signature);
+    }
+    for decorator in decorators {
+        query = query.with_decorator(decorator);
+    }
+    Ok(query)
+}
+
+/// The short form of a query, as used in config files, logs and on the command line:
+///
+/// | Query                 | Short form                    |
+/// |-----------------------|-------------------------------|
+/// | `ClassConstructor`    | `new Undici`                  |
+/// | `FunctionConstructor` | `new Connection()`            |
+/// | `ClassMethod`         | `Undici#fetch:async`          |
+/// | `ObjectMethod`        | `{}.query:async`              |
+/// | `FunctionDeclaration` | `fetch():sync`                |
+/// | `FunctionExpression`  | `fetch = function():sync`     |
+/// | `Exported`            | `export Undici2.fetch:sync`   |
+/// | `Location`            | `at 5:79:sync`                |
+/// | `Fingerprint`         | `fingerprint 1a2b3c4d:sync`   |
+/// | `Selector`            | `select Class > Method:async` |
+///
+/// A non-zero `index` follows in brackets, as in `Undici#fetch:async[2]`. Scope and signature
+/// restrictions follow after `where`, separated by commas, as in
+/// `fetch():sync where top-level, params(url, opts), !rest`. The clauses are `top-level`,
+/// `within a > b` and `unshadowed` for a [`FunctionScope`], and `params(a, b)`, `params=2`,
+/// `rest`, `default`, `async` and `generator` for a [`FunctionSignature`], where flags can be
+/// negated with `!`. A [`DecoratorQuery`] is written as `@Get`, or `@Get(/users)` to match
+/// arguments as well.
+///
+/// The alternatives of a [`FunctionQuery::FirstOf`] are separated by ` | `, as in
+/// `Client#_doRequest:async | Client#dispatch:async`. A name that could be mistaken for any of
+/// this syntax, such as the regex `/a | b/`, is written in double quotes, with backslashes
+/// and double quotes within escaped by a backslash, so the short form always parses back to the
+/// same query.
+impl Display for FunctionQuery {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let FunctionQuery::FirstOf { alternatives } = self {
+            for (i, alternative) in alternatives.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " | ")?;
+                }
+                write!(f, "{alternative}")?;
+            }
+            return Ok(());
+        }
+        // Restrictions are written innermost first, the order they're usually applied in.
+        let mut query = self;
+        let mut clauses = vec![];
+        loop {
+            match query {
+                FunctionQuery::Scoped {
+                    query: inner,
+                    scope,
+                } => {
+                    clauses.push(scope.clauses());
+                    query = inner;
+                }
+                FunctionQuery::Signature {
+                    query: inner,
+                    signature,
+                } => {
+                    clauses.push(signature.clauses());
+                    query = inner;
+                }
+                FunctionQuery::Decorated {
+                    query: inner,
+                    decorator,
+                } => {
+                    clauses.push(vec![decorator.to_string()]);
+                    query = inner;
+                }
+                _ => break,
+            }
+        }
+        match query {
+            FunctionQuery::ClassConstructor { class_name, .. } => {
+                write!(f, "new {}", quote(class_name, &[]))?;
+            }
+            FunctionQuery::FunctionConstructor { function_name, .. } => {
+                write!(f, "new {}()", quote(function_name, &[]))?;
+            }
+            FunctionQuery::ClassMethod {
+                class_name,
+                method_name,
+                kind,
+                ..
+            } => write!(
+                f,
+                "{}#{}:{kind}",
+                quote(class_name, &['#']),
+                quote(method_name, &[])
+            )?,
+            FunctionQuery::ObjectMethod {
+                method_name, kind, ..
+            } => write!(f, "{{}}.{}:{kind}", quote(method_name, &[]))?,
+            FunctionQuery::FunctionDeclaration {
+                function_name,
+                kind,
+                ..
+            } => write!(f, "{}():{kind}", quote(function_name, &[]))?,
+            FunctionQuery::FunctionExpression {
+                expression_name,
+                kind,
+                ..
+            } => write!(f, "{} = function():{kind}", quote(expression_name, &[]))?,
+            FunctionQuery::Exported {
+                export_path, kind, ..
+            } => write!(f, "export {}:{kind}", quote(export_path, &[]))?,
+            FunctionQuery::Location { range, kind, .. } => write!(f, "at {range}:{kind}")?,
+            FunctionQuery::Fingerprint {
+                fingerprint, kind, ..
+            } => write!(f, "fingerprint {}:{kind}", quote(fingerprint, &[]))?,
+            FunctionQuery::Selector { selector, kind, .. } => {
+                write!(f, "select {selector}:{kind}")?;
+            }
+            FunctionQuery::Scoped { .. }
+            | FunctionQuery::Signature { .. }
+            | FunctionQuery::Decorated { .. }
+            | FunctionQuery::FirstOf { .. } => {}
+        }
+        if query.index() > 0 {
+            write!(f, "[{}]", query.index())?;
+        }
+        clauses.reverse();
+        let clauses = clauses.concat();
+        if !clauses.is_empty() {
+            write!(f, " where {}", clauses.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FunctionQuery {
+    type Err = OrchestrionError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let alternatives = split_unquoted(input, " | ");
+        if alternatives.len() > 1 {
+            let query = FunctionQuery::FirstOf {
+                alternatives: alternatives
+                    .into_iter()
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()?,
+            };
+            query.validate()?;
+            return Ok(query);
+        }
+
+        let (base, clauses) = match find_unquoted(input, " where ") {
+            Some(i) => (&input[..i], Some(&input[i + " where ".len()..])),
+            None => (input, None),
+        };
+        let mut query = parse_base(input, base)?;
+
+        if let Some(clauses) = clauses {
+            query = restrict(query, clauses)
+                .map_err(|reason| format!("Invalid function query {input:?}: {reason}"))?;
+        }
+
+        query.validate()?;
+        Ok(query)
+    }
 }
@@ -0,0 +1,72 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
+ **/
+use swc_core::ecma::{
+    ast::{CallExpr, Callee, Expr, Function, MemberProp, Stmt},
+    atoms::Atom,
+};
+
+/// Skips over parentheses and the `(0, helper)` sequences module transforms call imported
+/// functions through.
+pub(crate) fn unwrap_callee(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(paren) => unwrap_callee(&paren.expr),
+        Expr::Seq(seq) => seq.exprs.last().map_or(expr, |last| unwrap_callee(last)),
+        _ => expr,
+    }
+}
+
+/// The name of the helper that TypeScript or Babel call as `callee`, without the leading
+/// underscores and any trailing digits that bundlers and module transforms add. Imported
+/// helpers are named by the property they're read from, unless that's the `default` export of
+/// a module, as in `tslib_1.__awaiter` or `(0, _createClass2.default)`.
+pub(crate) fn helper_name(callee: &Expr) -> Option<&str> {
+    let name: &str = match unwrap_callee(callee) {
+        Expr::Ident(ident) => &ident.sym,
+        Expr::Member(member) => match &member.prop {
+            MemberProp::Ident(prop) if prop.sym == "default" => return helper_name(&member.obj),
+            MemberProp::Ident(prop) => &prop.sym,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(
+        name.trim_start_matches('_')
+            .trim_end_matches(|c: char| c.is_ascii_digit()),
+    )
+}
+
+/// Whether `call` calls the helper with the given name, as returned by [`helper_name`].
+pub(crate) fn calls_helper(call: &CallExpr, name: &str) -> bool {
+    match &call.callee {
+        Callee::Expr(callee) => helper_name(callee) == Some(name),
+        _ => false,
+    }
+}
+
+/// The name of the class a function is the constructor of, if it's a class compiled by Babel,
+/// which checks that it's called with `new` by calling `_classCallCheck(this, Foo)`.
+pub(crate) fn babel_constructor_class(func: &Function) -> Option<Atom> {
+    func.body.as_ref()?.stmts.iter().find_map(|stmt| {
+        let Stmt::Expr(expr) = stmt else {
+            return None;
+        };
+        let Expr::Call(call) = unwrap_callee(&expr.expr) else {
+            return None;
+        };
+        if !calls_helper(call, "classCallCheck") {
+            return None;
+        }
+        Some(call.args.get(1)?.expr.as_ident()?.sym.clone())
+    })
+}
+
+/// The name of the class whose methods Babel defines with `call`, if it's a call of its
+/// `_createClass` helper, as in `_createClass(Foo, [{ key: "bar", value: function bar() {} }])`.
+pub(crate) fn babel_created_class(call: &CallExpr) -> Option<Atom> {
+    if !calls_helper(call, "createClass") {
+        return None;
+    }
+    Some(call.args.first()?.expr.as_ident()?.sym.clone())
+}
@@ -2,18 +2,30 @@
  * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
  * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
  **/
-use crate::config::InstrumentationConfig;
+use crate::analysis::{prop_name_str, Analysis};
+use crate::config::{Codegen, InstrumentationConfig};
+use crate::function_query::{name_matches, FunctionKind, FunctionQuery, FunctionType, ParamShape};
+use crate::helpers::{babel_constructor_class, babel_created_class};
+use crate::selector::Selector;
+use crate::template::Placeholders;
+use std::collections::HashMap;
 use std::path::PathBuf;
-use swc_core::common::{Span, SyntaxContext};
+use std::rc::Rc;
+use swc_core::common::{errors::SourceMapperDyn, sync::Lrc, util::take::Take, Span, SyntaxContext};
 use swc_core::ecma::{
     ast::{
-        ArrowExpr, AssignExpr, AssignTarget, BlockStmt, ClassDecl, ClassMethod, Constructor, Expr,
-        FnDecl, FnExpr, Ident, Lit, MemberProp, MethodProp, Module, ModuleItem, Pat, PropName,
-        Script, SimpleAssignTarget, Stmt, Str, VarDecl,
+        ArrayPat, ArrowExpr, AssignExpr, AssignOp, AssignTarget, AssignTargetPat, BindingIdent,
+        BlockStmt, CallExpr, Callee, Class, ClassDecl, ClassMember, ClassMethod, Constructor, Expr,
+        ExprOrSpread, ExprStmt, FnDecl, FnExpr, Function, Ident, IdentName, KeyValueProp, Lit,
+        MemberExpr, MemberProp, MetaPropExpr, MetaPropKind, MethodProp, Module, ObjectLit,
+        ObjectPat, Param, ParamOrTsParamProp, Pat, PrivateName, Prop, PropName, PropOrSpread,
+        RestPat, Script, SimpleAssignTarget, SpreadElement, Stmt, Str, Super, TryStmt,
+        TsParamPropParam, TsTypeAnn, VarDecl,
     },
     atoms::Atom,
+    visit::{Visit, VisitMut, VisitMutWith, VisitWith},
 };
-use swc_core::quote;
+use swc_core::{quote, quote_expr};
 
 macro_rules! ident {
     ($name:expr) => {
@@ -21,39 +33,201 @@ macro_rules! ident {
     };
 }
 
-/// An [`Instrumentation`] instance represents a single instrumentation configuration, and implements
-/// SWC's [`VisitMut`] trait to insert tracing code into matching functions. You can use this
-/// wherever you would use a [`VisitMut`] instance, such as within an SWC plugin, for example.
+/// An [`Instrumentation`] instance represents a single query of an instrumentation
+/// configuration, and implements SWC's [`VisitMut`] trait to insert tracing code into matching
+/// functions. You can use this wherever you would use a [`VisitMut`] instance, such as within an
+/// SWC plugin, for example.
 ///
 /// [`Instrumentation`]: Instrumentation
 /// [`VisitMut`]: https://rustdoc.swc.rs/swc_core/ecma/visit/trait.VisitMut.html
 #[derive(Debug)]
 pub struct Instrumentation {
     config: InstrumentationConfig,
-    count: usize,
+    /// The query as configured, which may have alternatives to choose from for each file.
+    configured_query: FunctionQuery,
+    /// The query matched against the current file.
+    function_query: FunctionQuery,
+    counts: HashMap<String, usize>,
     is_correct_class: bool,
+    /// Whether the class being visited extends another, so `this` isn't initialized in its
+    /// constructor until `super()` has been called.
+    is_derived_class: bool,
+    class_name: Option<String>,
+    channels: Vec<String>,
+    analysis: Option<Rc<Analysis>>,
+    /// Variables holding the prototype of a class, by the name of the class.
+    prototype_aliases: HashMap<Atom, Atom>,
+    /// Whether a declaration can be added right before the statement being visited, in the same
+    /// scope as the node being visited, which is set by the [`InstrumentationVisitor`].
+    ///
+    /// [`InstrumentationVisitor`]: crate::InstrumentationVisitor
+    can_hoist: bool,
+    /// Functions hoisted out of traced functions, waiting to be declared by the
+    /// [`InstrumentationVisitor`] right before the statement they were found in.
+    ///
+    /// [`InstrumentationVisitor`]: crate::InstrumentationVisitor
+    hoisted: Vec<Stmt>,
+    /// The version of the module being visited, for the metadata of traced functions.
+    module_version: String,
+    source_map: Option<SourceMap>,
+    /// The declarations of the metadata of the functions traced so far. See
+    /// [`InstrumentationConfig::site_metadata`].
+    sites: Vec<Stmt>,
+    mode: Mode,
+}
+
+/// What an [`Instrumentation`] does with the functions it matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Inserts tracing code into them.
+    Instrument,
+    /// Only records which functions would be traced, leaving the program untouched. See
+    /// [`Instrumentation::probe`].
+    Probe,
 }
 
 impl Instrumentation {
+    /// Creates an instrumentation for the first query of a configuration, which is its only
+    /// query if it was created with [`InstrumentationConfig::new`]. Configurations with several
+    /// queries need an instrumentation for each of them, created with
+    /// [`Instrumentation::for_query`].
+    /// # Panics
+    /// Panics if the configuration has no queries.
     #[must_use]
     pub fn new(config: InstrumentationConfig) -> Self {
+        let function_query = config
+            .function_queries
+            .first()
+            .cloned()
+            .expect("InstrumentationConfig has no function queries");
+        Self::for_query(config, function_query)
+    }
+
+    /// Creates an instrumentation for one of the queries of a configuration.
+    #[must_use]
+    pub fn for_query(config: InstrumentationConfig, function_query: FunctionQuery) -> Self {
         Self {
             config,
-            count: 0,
+            configured_query: function_query.clone(),
+            function_query,
+            counts: HashMap::new(),
             is_correct_class: false,
+            is_derived_class: false,
+            class_name: None,
+            channels: vec![],
+            analysis: None,
+            prototype_aliases: HashMap::new(),
+            can_hoist: false,
+            hoisted: vec![],
+            module_version: String::new(),
+            source_map: None,
+            sites: vec![],
+            mode: Mode::Instrument,
         }
     }
 
     pub(crate) fn reset(&mut self) {
-        self.count = 0;
+        self.counts.clear();
         self.is_correct_class = false;
+        self.is_derived_class = false;
+        self.class_name = None;
+        self.channels.clear();
+        self.analysis = None;
+        self.prototype_aliases.clear();
+        self.hoisted.clear();
+        self.sites.clear();
+        if self.configured_query.alternatives().is_some() {
+            self.function_query = self.configured_query.clone();
+        }
     }
 
-    fn new_fn(&self, body: BlockStmt) -> ArrowExpr {
+    /// Whether matching this instrumentation needs an [`Analysis`] of the whole program, which
+    /// has to be done before any of it is visited.
+    pub(crate) fn needs_analysis(&self) -> bool {
+        self.configured_query.needs_analysis()
+    }
+
+    pub(crate) fn needs_fingerprints(&self) -> bool {
+        self.configured_query.needs_fingerprints()
+    }
+
+    pub(crate) fn selectors(&self) -> Vec<&Selector> {
+        self.configured_query.selectors()
+    }
+
+    pub(crate) fn alternatives(&self) -> Option<&[FunctionQuery]> {
+        self.configured_query.alternatives()
+    }
+
+    /// Whether traced functions run a [`CodeTemplate`](crate::CodeTemplate) rather than publish
+    /// on their channel, which then doesn't need to be declared.
+    pub(crate) fn uses_template(&self) -> bool {
+        self.config.template.is_some()
+    }
+
+    pub(crate) fn channel_name_template(&self) -> &str {
+        &self.config.channel_name
+    }
+
+    /// Creates a copy of this instrumentation that only tries one of its alternatives, to find
+    /// out whether that alternative matches anything in a file. Probes never change the program
+    /// they visit, so they can visit the one that's about to be instrumented.
+    pub(crate) fn probe(&self, alternative: &FunctionQuery) -> Instrumentation {
+        let mut probe = Instrumentation::for_query(self.config.clone(), alternative.clone());
+        probe.analysis.clone_from(&self.analysis);
+        probe.source_map.clone_from(&self.source_map);
+        probe.module_version.clone_from(&self.module_version);
+        probe.mode = Mode::Probe;
+        probe
+    }
+
+    /// Whether this instrumentation has traced any function since it was last reset.
+    pub(crate) fn traced_any(&self) -> bool {
+        !self.channels.is_empty()
+    }
+
+    /// Applies one of the alternatives of the configured query to the current file.
+    pub(crate) fn apply_alternative(&mut self, alternative: FunctionQuery) {
+        self.function_query = alternative;
+    }
+
+    pub(crate) fn set_analysis(&mut self, analysis: Rc<Analysis>) {
+        self.analysis = Some(analysis);
+    }
+
+    pub(crate) fn set_module_version(&mut self, version: &str) {
+        version.clone_into(&mut self.module_version);
+    }
+
+    pub(crate) fn set_source_map(&mut self, source_map: Lrc<SourceMapperDyn>) {
+        self.source_map = Some(SourceMap(source_map));
+    }
+
+    pub(crate) fn take_sites(&mut self) -> Vec<Stmt> {
+        std::mem::take(&mut self.sites)
+    }
+
+    pub(crate) fn set_can_hoist(&mut self, can_hoist: bool) {
+        self.can_hoist = can_hoist;
+    }
+
+    pub(crate) fn hoisted_len(&self) -> usize {
+        self.hoisted.len()
+    }
+
+    /// Takes the functions hoisted since there were `from` of them.
+    pub(crate) fn take_hoisted(&mut self, from: usize) -> Vec<Stmt> {
+        self.hoisted.split_off(from)
+    }
+
+    /// Creates the arrow function the original body is moved into. It's only `async` if the
+    /// original function is, since downleveled async functions already return a promise and
+    /// their body may be compiled for runtimes without `async`.
+    fn new_fn(body: BlockStmt, is_async: bool) -> ArrowExpr {
         ArrowExpr {
             params: vec![],
             body: Box::new(body.into()),
-            is_async: self.config.function_query.kind().is_async(),
+            is_async,
             is_generator: false,
             type_params: None,
             return_type: None,
@@ -62,17 +236,51 @@ impl Instrumentation {
         }
     }
 
-    fn create_tracing_channel(&self) -> Stmt {
-        let ch_str = ident!(format!("tr_ch_apm${}", self.config.channel_name));
-        let channel_string = Expr::Lit(Lit::Str(Str {
+    /// Matches are counted separately for each name a query's patterns can match, so that
+    /// `index` keeps its meaning for every one of them.
+    fn count_key(&self, name: &str) -> String {
+        match (&self.class_name, self.function_query.class_name()) {
+            (Some(class), Some(_)) => format!("{class}.{name}"),
+            _ => name.to_string(),
+        }
+    }
+
+    fn count(&mut self, name: &str) -> &mut usize {
+        let key = self.count_key(name);
+        self.counts.entry(key).or_default()
+    }
+
+    /// Fills in the `{class}` and `{name}` placeholders of the configured channel name with those
+    /// of the matched function.
+    fn channel_name(&self, name: &str) -> String {
+        self.config
+            .channel_name
+            .replace("{class}", self.class_name.as_deref().unwrap_or_default())
+            .replace("{name}", name)
+    }
+
+    /// Returns the channel to publish on for a matched function, remembering it so that it's
+    /// declared once the whole program has been visited.
+    fn use_channel(&mut self, name: &str) -> String {
+        let channel = self.channel_name(name);
+        if !self.channels.contains(&channel) {
+            self.channels.push(channel.clone());
+        }
+        channel
+    }
+
+    /// The name of a channel as subscribers know it, including the name of the module.
+    fn full_channel_name(&self, channel: &str) -> Expr {
+        Expr::Lit(Lit::Str(Str {
             span: Span::default(),
-            value: format!(
-                "orchestrion:{}:{}",
-                self.config.module.name, self.config.channel_name
-            )
-            .into(),
+            value: format!("orchestrion:{}:{channel}", self.config.module.name).into(),
             raw: None,
-        }));
+        }))
+    }
+
+    pub(crate) fn create_tracing_channel(&self, channel: &str) -> Stmt {
+        let ch_str = ident!(format!("tr_ch_apm${channel}"));
+        let channel_string = self.full_channel_name(channel);
         let define_channel = quote!(
             "const $ch = tr_ch_apm_tracingChannel($channel_str);" as Stmt,
             ch = ch_str,
@@ -81,10 +289,36 @@ impl Instrumentation {
         define_channel
     }
 
-    fn insert_tracing(&mut self, body: &mut BlockStmt) {
-        self.count += 1;
+    /// Inserts tracing into a function. `own_name` is the name a function expression is bound to
+    /// within its own body, if any.
+    fn insert_tracing(&mut self, func: &mut Function, name: &str, own_name: Option<&Ident>) {
+        if self.mode == Mode::Probe {
+            if func.body.is_some() {
+                self.use_channel(name);
+            }
+            return;
+        }
+        if self.config.codegen == Codegen::Hoisted
+            && self.config.template.is_none()
+            && self.can_hoist
+            && can_hoist(func, own_name)
+        {
+            self.insert_hoisted_tracing(func, name);
+            return;
+        }
+        let is_async = func.is_async;
+        let span = func.span;
+        let rebind = self.rebind_parameters(
+            func.params.iter().map(|param| &param.pat),
+            &ident!("__apm$ctx"),
+        );
+        let Some(body) = func.body.as_mut() else {
+            return;
+        };
+        let channel = self.use_channel(name);
 
-        let original_stmts = std::mem::take(&mut body.stmts);
+        let mut original_stmts = std::mem::take(&mut body.stmts);
+        let directives = take_directives(&mut original_stmts);
 
         // Create a new BlockStmt with the original statements
         let original_body = BlockStmt {
@@ -93,89 +327,706 @@ impl Instrumentation {
             ..body.clone()
         };
 
-        let traced_fn = self.new_fn(original_body);
+        let traced_fn = Self::new_fn(original_body, is_async);
 
-        let ch_ident = ident!(format!("tr_ch_apm${}", &self.config.channel_name));
+        let ch_ident = ident!(format!("tr_ch_apm${channel}"));
         let trace_ident = ident!(format!(
-            "tr_ch_apm${}.{}",
-            &self.config.channel_name,
-            self.config.function_query.kind().tracing_operator()
+            "tr_ch_apm${channel}.{}",
+            self.function_query.kind().tracing_operator()
         ));
+        let context = self.function_context(&channel, span);
 
-        body.stmts = vec![
+        body.stmts = directives;
+        if let Some(template) = &self.config.template {
+            body.stmts.push(quote!(
+                "const __apm$traced = $traced;" as Stmt,
+                traced: Expr = traced_fn.into()
+            ));
+            if template.uses_context() {
+                body.stmts.push(quote!(
+                    "const __apm$ctx = $context;" as Stmt,
+                    context: Expr = context
+                ));
+            }
+            body.stmts.extend(template.instantiate(&Placeholders {
+                body: *quote_expr!("__apm$traced"),
+                channel: self.full_channel_name(&channel),
+                context: *quote_expr!("__apm$ctx"),
+                kind: Expr::from(self.function_query.kind().to_string()),
+            }));
+            return;
+        }
+        body.stmts.extend([
             quote!("const __apm$traced = $traced;" as Stmt, traced: Expr = traced_fn.into()),
             quote!(
                 "if (!$ch.hasSubscribers) return __apm$traced();" as Stmt,
                 ch = ch_ident
             ),
-            quote!(
-                "return $trace(__apm$traced, { arguments, self: this } );" as Stmt,
-                trace = trace_ident
+        ]);
+        // Advice runs within the traced function, so only once `start` has been published.
+        let mut advice: Vec<Stmt> = self.skip_advice().into_iter().chain(rebind).collect();
+        if !advice.is_empty() || self.config.around_advice {
+            advice.push(quote!("return __apm$traced();" as Stmt));
+            let traced = Self::new_fn(
+                BlockStmt {
+                    stmts: advice,
+                    ..Default::default()
+                },
+                false,
+            );
+            body.stmts.push(quote!(
+                "const __apm$ctx = $context;" as Stmt,
+                context: Expr = context
+            ));
+            body.stmts
+                .extend(self.return_traced(trace_ident, traced.into()));
+        } else {
+            body.stmts.push(quote!(
+                "return $trace(__apm$traced, $context);" as Stmt,
+                trace = trace_ident,
+                context: Expr = context,
+            ));
+        }
+    }
+
+    /// Moves the body of a function into a function declared next to it, which the function then
+    /// calls directly when nothing is subscribed, and traces otherwise. See [`Codegen::Hoisted`].
+    fn insert_hoisted_tracing(&mut self, func: &mut Function, name: &str) {
+        let Some(body) = func.body.as_mut() else {
+            return;
+        };
+        let channel = self.use_channel(name);
+
+        let original_body = BlockStmt {
+            span: body.span,
+            ctxt: body.ctxt,
+            stmts: std::mem::take(&mut body.stmts),
+        };
+        // Both functions need the directives, since `'use strict'` decides how `this` is passed
+        // on as well as how the body runs. Their parameters are simple, so it's allowed in both.
+        let directives = directive_prologue(&original_body.stmts).to_vec();
+        let original_ident = ident!(format!("tr_ch_apm_orig${channel}${}", func.span.lo.0));
+        let original = FnDecl {
+            ident: original_ident.clone(),
+            declare: false,
+            function: Box::new(Function {
+                params: func
+                    .params
+                    .iter()
+                    .map(|param| Param {
+                        decorators: vec![],
+                        ..param.clone()
+                    })
+                    .collect(),
+                // Without the original span, the declaration isn't matched again through the
+                // analysis of the program.
+                span: Span::default(),
+                ctxt: SyntaxContext::empty(),
+                body: Some(original_body),
+                decorators: vec![],
+                is_generator: false,
+                is_async: func.is_async,
+                type_params: func.type_params.clone(),
+                return_type: func.return_type.clone(),
+            }),
+        };
+        self.hoisted.push(Stmt::Decl(original.into()));
+
+        let ch_ident = ident!(format!("tr_ch_apm${channel}"));
+        let trace_ident = ident!(format!(
+            "tr_ch_apm${channel}.{}",
+            self.function_query.kind().tracing_operator()
+        ));
+        let context = self.function_context(&channel, func.span);
+        body.stmts = directives;
+        body.stmts.extend([quote!(
+            "if (!$ch.hasSubscribers) return $original.apply(this, arguments);" as Stmt,
+            ch = ch_ident,
+            original = original_ident.clone(),
+        )]);
+        let rebind = self.config.rebind_parameters && !func.params.is_empty();
+        if rebind || self.config.around_advice {
+            // The hoisted function is called with the context's arguments, and only once `start`
+            // has been published, so that it can be skipped.
+            let arguments = if rebind {
+                quote_expr!("__apm$ctx.arguments")
+            } else {
+                quote_expr!("arguments")
+            };
+            let mut advice: Vec<Stmt> = self.skip_advice().into_iter().collect();
+            advice.push(quote!(
+                "return $original.apply(this, $arguments);" as Stmt,
+                original = original_ident,
+                arguments: Expr = *arguments,
+            ));
+            let traced = Self::new_fn(
+                BlockStmt {
+                    stmts: advice,
+                    ..Default::default()
+                },
+                false,
+            );
+            body.stmts.push(quote!(
+                "const __apm$ctx = $context;" as Stmt,
+                context: Expr = context
+            ));
+            body.stmts
+                .extend(self.return_traced(trace_ident, traced.into()));
+        } else {
+            body.stmts.push(quote!(
+                "return $trace($original, $context, this, ...arguments);" as Stmt,
+                trace = trace_ident,
+                original = original_ident,
+                context: Expr = context,
+            ));
+        }
+    }
+
+    /// The context published for the function at `span`, with anything added to it by the
+    /// configuration.
+    fn function_context(&mut self, channel: &str, span: Span) -> Expr {
+        let mut context = quote_expr!("{ arguments, self: this }");
+        if let (Expr::Object(object), Some(builder)) = (&mut *context, &self.config.context_builder)
+        {
+            // A builder that throws mustn't break the traced function, so nothing is added then.
+            let built = quote_expr!(
+                "(() => { try { return $built; } catch (tr_ch_err) {} })()",
+                built: Expr = ObjectLit {
+                    span: Span::default(),
+                    props: builder.props().to_vec(),
+                }
+                .into(),
+            );
+            object.props.push(PropOrSpread::Spread(SpreadElement {
+                dot3_token: Span::default(),
+                expr: built,
+            }));
+        }
+        self.with_site(*context, channel, span)
+    }
+
+    /// Adds the metadata of the function at `span` to its `context` as `site`, declaring it for
+    /// the whole program, if the configuration asks for it. See
+    /// [`InstrumentationConfig::site_metadata`].
+    fn with_site(&mut self, mut context: Expr, channel: &str, span: Span) -> Expr {
+        if !self.config.site_metadata {
+            return context;
+        }
+        let mut metadata = vec![
+            ("module", Expr::from(self.config.module.name.clone())),
+            ("version", Expr::from(self.module_version.clone())),
+            (
+                "file",
+                Expr::from(self.config.module.file_path.to_string_lossy()),
             ),
+            ("query", Expr::from(self.function_query.to_string())),
         ];
+        if let Some(source_map) = &self.source_map {
+            let loc = source_map.0.lookup_char_pos(span.lo);
+            metadata.push(("line", Expr::from(loc.line)));
+            metadata.push(("column", Expr::from(loc.col.0 + 1)));
+        }
+        let site_ident = ident!(format!("tr_ch_apm_site${channel}${}", span.lo.0));
+        self.sites.push(quote!(
+            "const $site = Object.freeze($metadata);" as Stmt,
+            site = site_ident.clone(),
+            metadata: Expr = ObjectLit {
+                span: Span::default(),
+                props: metadata
+                    .into_iter()
+                    .map(|(key, value)| key_value(key, value))
+                    .collect(),
+            }
+            .into(),
+        ));
+        if let Expr::Object(object) = &mut context {
+            object.props.push(key_value("site", site_ident.into()));
+        }
+        context
     }
 
-    fn insert_constructor_tracing(&mut self, body: &mut BlockStmt) {
-        self.count += 1;
+    /// Skips the original function once `start` has been published, if a subscriber asked for
+    /// it. See [`InstrumentationConfig::around_advice`].
+    fn skip_advice(&self) -> Option<Stmt> {
+        self.config
+            .around_advice
+            .then(|| quote!("if (__apm$ctx.skip) return __apm$ctx.result;" as Stmt))
+    }
 
-        let original_stmts = std::mem::take(&mut body.stmts);
+    /// Traces the `traced` function with the `__apm$ctx` context, returning what it returns, or
+    /// with around advice, the context's `result` once subscribers have seen it.
+    fn return_traced(&self, trace: Ident, traced: Expr) -> Vec<Stmt> {
+        if !self.config.around_advice {
+            return vec![quote!(
+                "return $trace($traced, __apm$ctx);" as Stmt,
+                trace = trace,
+                traced: Expr = traced,
+            )];
+        }
+        match self.function_query.kind() {
+            FunctionKind::Sync => vec![
+                quote!(
+                    "$trace($traced, __apm$ctx);" as Stmt,
+                    trace = trace,
+                    traced: Expr = traced,
+                ),
+                quote!("return __apm$ctx.result;" as Stmt),
+            ],
+            // The promise returned by `tracePromise` settles once `asyncEnd` has been published.
+            FunctionKind::Async => vec![quote!(
+                "return $trace($traced, __apm$ctx).then(() => __apm$ctx.result);" as Stmt,
+                trace = trace,
+                traced: Expr = traced,
+            )],
+        }
+    }
 
-        let ch_ident = ident!(format!("tr_ch_apm${}", &self.config.channel_name));
-        let ctx_ident = ident!(format!("tr_ch_apm_ctx${}", &self.config.channel_name));
-        let mut try_catch = quote!(
-            "try {
-                if ($ch.hasSubscribers) {
-                    $ch.start.publish($ctx);
-                }
-            } catch (tr_ch_err) { 
-                if ($ch.hasSubscribers) {
-                    $ctx.error = tr_ch_err;
-                    try {
+    /// Assigns the parameters of a traced function from the `arguments` of its context, as if
+    /// they were bound again, as in `[url, options = {}] = __apm$ctx.arguments;`, if the
+    /// configuration asks for it. See [`InstrumentationConfig::rebind_parameters`].
+    fn rebind_parameters<'p>(
+        &self,
+        params: impl IntoIterator<Item = &'p Pat>,
+        ctx: &Ident,
+    ) -> Option<Stmt> {
+        if !self.config.rebind_parameters {
+            return None;
+        }
+        let mut elems: Vec<Option<Pat>> = params
+            .into_iter()
+            // TypeScript's `this` parameter isn't passed as an argument.
+            .filter(|pat| !matches!(pat, Pat::Ident(ident) if ident.id.sym == "this"))
+            .cloned()
+            .map(Some)
+            .collect();
+        if elems.is_empty() {
+            return None;
+        }
+        elems.visit_mut_with(&mut StripTypeAnnotations);
+        let assign = AssignExpr {
+            span: Span::default(),
+            op: AssignOp::Assign,
+            left: AssignTarget::Pat(AssignTargetPat::Array(ArrayPat {
+                span: Span::default(),
+                elems,
+                optional: false,
+                type_ann: None,
+            })),
+            right: quote_expr!("$ctx.arguments", ctx = ctx.clone()),
+        };
+        Some(Stmt::Expr(ExprStmt {
+            span: Span::default(),
+            expr: Box::new(Expr::Assign(assign)),
+        }))
+    }
+
+    /// Wraps the body of a constructor to publish the start, end and any error of constructing an
+    /// object, along with the object itself as `self`.
+    fn insert_constructor_tracing(
+        &mut self,
+        body: &mut BlockStmt,
+        name: &str,
+        kind: ConstructorKind,
+        params: &[Pat],
+        span: Span,
+    ) {
+        let channel = self.use_channel(name);
+        if self.mode == Mode::Probe {
+            return;
+        }
+
+        let mut original_stmts = std::mem::take(&mut body.stmts);
+        let directives = take_directives(&mut original_stmts);
+
+        let ch_ident = ident!(format!("tr_ch_apm${channel}"));
+        let ctx_ident = ident!(format!("tr_ch_apm_ctx${channel}"));
+        let context = self.with_site(*quote_expr!("{ arguments }"), &channel, span);
+        let mut active = quote_expr!("$ch.hasSubscribers", ch = ch_ident.clone());
+        if matches!(kind, ConstructorKind::Function) {
+            let new_target = Expr::MetaProp(MetaPropExpr {
+                span: Span::default(),
+                kind: MetaPropKind::NewTarget,
+            });
+            active = quote_expr!(
+                "$new_target && $active",
+                new_target: Expr = new_target,
+                active: Expr = *active,
+            );
+        }
+        let mut try_catch = if matches!(kind, ConstructorKind::Derived) {
+            // `this` can't be touched until `super()` has returned it, and touching it after
+            // `super()` throws would replace the error with a `ReferenceError`, so `self` is
+            // taken from what `super()` returns instead.
+            original_stmts.visit_mut_with(&mut SuperCallRewriter {
+                ctx: ctx_ident.clone(),
+            });
+            quote!(
+                "try {
+                    if ($active) {
+                        $ch.start.publish($ctx);
+                    }
+                } catch (tr_ch_err) {
+                    if ($active) {
+                        $ctx.error = tr_ch_err;
+                        $ch.error.publish($ctx);
+                    }
+                    throw tr_ch_err;
+                } finally {
+                    if ($active) {
+                        $ch.end.publish($ctx);
+                    }
+                }" as Stmt,
+                active: Expr = *active,
+                ch = ch_ident,
+                ctx = ctx_ident.clone(),
+            )
+        } else {
+            quote!(
+                "try {
+                    if ($active) {
+                        $ch.start.publish($ctx);
+                    }
+                } catch (tr_ch_err) {
+                    if ($active) {
+                        $ctx.error = tr_ch_err;
                         $ctx.self = this;
-                    } catch (refErr) {
-                        // This can only error out if super hasn't been called yet.
-                        // Safe to ignore, but note that self/this won't get into the context.
+                        $ch.error.publish($ctx);
                     }
-                    $ch.error.publish($ctx);
-                }
-                throw tr_ch_err;
-            } finally {
-                if ($ch.hasSubscribers) {
-                    $ctx.self = this;
-                    $ch.end.publish($ctx);
-                }
-            }" as Stmt,
-            ch = ch_ident,
-            ctx = ctx_ident.clone(),
-        );
+                    throw tr_ch_err;
+                } finally {
+                    if ($active) {
+                        $ctx.self = this;
+                        $ch.end.publish($ctx);
+                    }
+                }" as Stmt,
+                active: Expr = *active,
+                ch = ch_ident,
+                ctx = ctx_ident.clone(),
+            )
+        };
         if let Some(try_catch_stmt) = try_catch.as_mut_try_stmt() {
-            for stmt in &original_stmts {
-                try_catch_stmt.block.stmts.push(stmt.clone());
-            }
+            self.insert_constructor_advice(try_catch_stmt, &ctx_ident, params);
+            try_catch_stmt.block.stmts.append(&mut original_stmts);
         }
 
-        body.stmts = vec![
-            quote!("const $ctx = { arguments };" as Stmt, ctx = ctx_ident,),
+        body.stmts = directives;
+        body.stmts.extend([
+            quote!(
+                "const $ctx = $context;" as Stmt,
+                ctx = ctx_ident,
+                context: Expr = context,
+            ),
             try_catch,
-        ];
+        ]);
+    }
+
+    /// Adds the advice asked for by the configuration to the code wrapping a constructor, which
+    /// runs right after `start` has been published, and, for the result, after `end` has.
+    fn insert_constructor_advice(&self, try_catch: &mut TryStmt, ctx: &Ident, params: &[Pat]) {
+        if let Some(start) = try_catch.block.stmts.first_mut().and_then(if_active) {
+            if self.config.around_advice {
+                start.push(quote!(
+                    "if ($ctx.skip) return $ctx.result;" as Stmt,
+                    ctx = ctx.clone()
+                ));
+            }
+            start.extend(self.rebind_parameters(params, ctx));
+        }
+        if let Some(end) = try_catch
+            .finalizer
+            .as_mut()
+            .and_then(|finalizer| finalizer.stmts.first_mut())
+            .and_then(if_active)
+        {
+            if self.config.around_advice {
+                // Errors have to be thrown on rather than replaced by the result.
+                end.push(quote!(
+                    "if ($ctx.result !== undefined && !(\"error\" in $ctx)) return $ctx.result;"
+                        as Stmt,
+                    ctx = ctx.clone()
+                ));
+            }
+        }
+    }
+
+    /// Whether functions declared within other functions or object methods can match the query.
+    /// Only queries resolved through the analysis, such as scoped queries, look inside them, so
+    /// that nested functions don't count towards the index of any other query.
+    fn matches_nested_functions(&self) -> bool {
+        self.function_query.needs_analysis()
+    }
+
+    /// Whether the function or constructor at the given span is within the scope the query is
+    /// restricted to. Functions out of scope must not be counted towards the query's index.
+    fn in_scope(&self, span: Span) -> bool {
+        let info = self
+            .analysis
+            .as_ref()
+            .and_then(|analysis| analysis.function(span));
+        self.function_query.matches_scope(info)
     }
 
     fn trace_expr_or_count(&mut self, func_expr: &mut FnExpr, name: &Atom) -> bool {
-        if self
-            .config
-            .function_query
-            .matches_expr(func_expr, &mut self.count, name.as_ref())
+        if self.trace_constructor_function_or_count(&mut func_expr.function, name) {
+            return true;
+        }
+        let key = self.count_key(name);
+        if self.in_scope(func_expr.function.span)
+            && self.function_query.matches_expr(
+                func_expr,
+                self.counts.entry(key).or_default(),
+                name.as_ref(),
+            )
             && func_expr.function.body.is_some()
         {
-            if let Some(body) = func_expr.function.body.as_mut() {
-                self.insert_tracing(body);
+            self.insert_tracing(&mut func_expr.function, name, func_expr.ident.as_ref());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Traces functions matched through the analysis of the whole program, such as by export
+    /// path or by fingerprint, whatever syntax they're declared with.
+    fn trace_analyzed_or_count(&mut self, func: &mut Function, own_name: Option<&Ident>) -> bool {
+        let analysis = self.analysis.clone();
+        let info = analysis
+            .as_ref()
+            .and_then(|analysis| analysis.function(func.span));
+        let query = &self.function_query;
+        let name = match query.typ() {
+            FunctionType::Export => {
+                let path = query.name();
+                path.rsplit('.').next().unwrap_or(path).to_string()
             }
+            _ => info
+                .and_then(|info| info.name.clone())
+                .unwrap_or_else(|| "anonymous".to_string()),
+        };
+        let key = self.count_key(query.name());
+        if self.in_scope(func.span)
+            && self
+                .function_query
+                .matches_analyzed(func, self.counts.entry(key).or_default(), info)
+            && func.body.is_some()
+        {
+            self.insert_tracing(func, &name, own_name);
             true
         } else {
             false
         }
     }
 
+    /// Starts matching the methods of a class with the given name.
+    fn enter_class(&mut self, name: &str) {
+        self.is_correct_class = self
+            .function_query
+            .class_name()
+            .is_none_or(|class| name_matches(class, name));
+        self.is_derived_class = false;
+        self.class_name = Some(name.to_string());
+    }
+
+    /// Traces a method of the class being visited, if it matches.
+    fn trace_method_or_count(
+        &mut self,
+        func: &mut Function,
+        name: &str,
+        own_name: Option<&Ident>,
+    ) -> bool {
+        // Only increment count when class matches
+        if !self.is_correct_class || !self.in_scope(func.span) {
+            return false;
+        }
+        let key = self.count_key(name);
+        if self
+            .function_query
+            .matches_method(func, self.counts.entry(key).or_default(), name)
+        {
+            self.insert_tracing(func, name, own_name);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether a constructor of the class being visited matches, apart from its `index`.
+    fn matches_constructor(&self, span: Span, params: &[ParamShape]) -> bool {
+        self.is_correct_class
+            && self.function_query.name() == "constructor"
+            && self.in_scope(span)
+            && self.function_query.matches_signature(params, false, false)
+            // Constructors can't be decorated.
+            && self.function_query.matches_decorators(&[])
+    }
+
+    /// Gives a class that relies on its implicit constructor an explicit one that does the same,
+    /// `constructor(...args) { super(...args); }` if it's derived and an empty one otherwise, if
+    /// the query would match it, so creating its instances can still be traced.
+    fn synthesize_constructor(&mut self, class: &mut Class) {
+        if class
+            .body
+            .iter()
+            .any(|member| matches!(member, ClassMember::Constructor(_)))
+        {
+            return;
+        }
+        let mut constructor = Constructor {
+            key: PropName::Ident(IdentName::new("constructor".into(), Span::default())),
+            body: Some(BlockStmt::default()),
+            ..Default::default()
+        };
+        if self.is_derived_class {
+            let args = ident!("args");
+            constructor
+                .params
+                .push(ParamOrTsParamProp::Param(Param::from(Pat::Rest(RestPat {
+                    span: Span::default(),
+                    dot3_token: Span::default(),
+                    arg: Box::new(Pat::Ident(args.clone().into())),
+                    type_ann: None,
+                }))));
+            let super_call = CallExpr {
+                callee: Callee::Super(Super::dummy()),
+                args: vec![ExprOrSpread {
+                    spread: Some(Span::default()),
+                    expr: Box::new(args.into()),
+                }],
+                ..Default::default()
+            };
+            constructor.body = Some(BlockStmt {
+                stmts: vec![Stmt::Expr(ExprStmt {
+                    span: Span::default(),
+                    expr: Box::new(super_call.into()),
+                })],
+                ..Default::default()
+            });
+        }
+        let params = ParamShape::of_constructor(&constructor);
+        if self.mode == Mode::Probe {
+            // The constructor would be traced once it's been added and visited.
+            self.trace_constructor_or_count(
+                constructor.span,
+                &params,
+                &[],
+                constructor.body.as_mut(),
+            );
+        } else if self.matches_constructor(constructor.span, &params) {
+            class.body.insert(0, ClassMember::Constructor(constructor));
+        }
+    }
+
+    /// Traces the constructor of the class being visited, if it matches.
+    fn trace_constructor_or_count(
+        &mut self,
+        span: Span,
+        params: &[ParamShape],
+        pats: &[Pat],
+        body: Option<&mut BlockStmt>,
+    ) {
+        // Overload signatures don't count towards `index`.
+        let Some(body) = body else {
+            return;
+        };
+        if !self.matches_constructor(span, params) {
+            return;
+        }
+
+        let index = self.function_query.index();
+        let count = self.count("constructor");
+        let is_at_index = *count == index;
+        *count += 1;
+        if is_at_index {
+            let kind = if self.is_derived_class {
+                ConstructorKind::Derived
+            } else {
+                ConstructorKind::Base
+            };
+            self.insert_constructor_tracing(body, "constructor", kind, pats, span);
+        }
+    }
+
+    /// Traces a plain function that's used as a constructor, if it matches. Whether it's called
+    /// with `new` is only known at runtime, so any function with a matching name counts.
+    fn trace_constructor_function_or_count(&mut self, func: &mut Function, name: &str) -> bool {
+        if !matches!(self.function_query.typ(), FunctionType::ConstructorFunction)
+            || !self.in_scope(func.span)
+        {
+            return false;
+        }
+        let key = self.count_key(name);
+        if !self.function_query.matches_constructor_function(
+            func,
+            self.counts.entry(key).or_default(),
+            name,
+        ) {
+            return false;
+        }
+        let Some(body) = func.body.as_mut() else {
+            return false;
+        };
+        // The function stands in for a class, so it fills in the `{class}` placeholder as well.
+        self.class_name = Some(name.to_string());
+        let pats: Vec<Pat> = func.params.iter().map(|param| param.pat.clone()).collect();
+        self.insert_constructor_tracing(body, name, ConstructorKind::Function, &pats, func.span);
+        true
+    }
+
+    /// Traces the method defined by one of the descriptors Babel passes to `_createClass`, such
+    /// as `{ key: "bar", value: function bar() {} }`.
+    fn trace_descriptor_or_count(&mut self, descriptor: &mut ObjectLit) {
+        let mut name = None;
+        let mut method = None;
+        for prop in &mut descriptor.props {
+            let PropOrSpread::Prop(prop) = prop else {
+                continue;
+            };
+            let Prop::KeyValue(prop) = &mut **prop else {
+                continue;
+            };
+            match prop_name_str(&prop.key).as_deref() {
+                Some("key") => {
+                    if let Expr::Lit(Lit::Str(key)) = &*prop.value {
+                        name = Some(key.value.to_string());
+                    }
+                }
+                Some("value") => method = prop.value.as_mut_fn_expr(),
+                _ => {}
+            }
+        }
+        if let (Some(name), Some(method)) = (name, method) {
+            self.trace_method_or_count(&mut method.function, &name, method.ident.as_ref());
+        }
+    }
+
+    /// The class whose prototype `expr` is, as in `Foo.prototype`, or `_proto` after Babel's
+    /// loose mode has declared `var _proto = Foo.prototype`.
+    fn prototype_of(&self, expr: &Expr) -> Option<Atom> {
+        match expr {
+            Expr::Ident(ident) => self.prototype_aliases.get(&ident.sym).cloned(),
+            Expr::Member(member) => match (&*member.obj, &member.prop) {
+                (Expr::Ident(class), MemberProp::Ident(prop)) if prop.sym == "prototype" => {
+                    Some(class.sym.clone())
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The class `expr` is, as in `Foo.create = function…`, where Babel's loose mode assigns
+    /// static methods. Only classes whose prototype it has declared an alias of are known to be
+    /// classes, so the static methods of classes without instance methods aren't found.
+    fn static_class_of(&self, expr: &Expr) -> Option<Atom> {
+        let Expr::Ident(ident) = expr else {
+            return None;
+        };
+        self.prototype_aliases
+            .values()
+            .find(|class| **class == ident.sym)
+            .cloned()
+    }
+
     #[must_use]
     pub fn matches(&self, module_name: &str, version: &str, file_path: &PathBuf) -> bool {
         self.config.matches(module_name, version, file_path)
@@ -185,36 +1036,71 @@ impl Instrumentation {
     // whether recusrsing through the tree is necessary, rather than calling
     // `visit_mut_children_with`.
 
-    pub fn visit_mut_module(&mut self, node: &mut Module) -> bool {
-        node.body
-            .insert(1, ModuleItem::Stmt(self.create_tracing_channel()));
+    /// The channels used by the functions traced so far, which are declared once the whole
+    /// program has been visited, since channel names can depend on the names of matched
+    /// functions.
+    pub(crate) fn channels(&self) -> &[String] {
+        &self.channels
+    }
+
+    pub fn visit_mut_module(&mut self, _node: &mut Module) -> bool {
         true
     }
 
-    pub fn visit_mut_script(&mut self, node: &mut Script) -> bool {
-        let start_index = get_script_start_index(node);
-        node.body
-            .insert(start_index + 1, self.create_tracing_channel());
+    pub fn visit_mut_script(&mut self, _node: &mut Script) -> bool {
         true
     }
 
     pub fn visit_mut_fn_decl(&mut self, node: &mut FnDecl) -> bool {
-        if self
-            .config
-            .function_query
-            .matches_decl(node, &mut self.count)
+        // Babel compiles classes to constructor functions that call `_classCallCheck`, and
+        // defines their methods separately.
+        if let Some(class) = babel_constructor_class(&node.function) {
+            self.enter_class(&class);
+            let params = ParamShape::of_function(&node.function);
+            let pats: Vec<Pat> = node
+                .function
+                .params
+                .iter()
+                .map(|param| param.pat.clone())
+                .collect();
+            self.trace_constructor_or_count(
+                node.function.span,
+                &params,
+                &pats,
+                node.function.body.as_mut(),
+            );
+            return true;
+        }
+        let name = node.ident.sym.clone();
+        if self.trace_constructor_function_or_count(&mut node.function, &name) {
+            return true;
+        }
+        let key = self.count_key(&name);
+        if self.in_scope(node.function.span)
+            && self
+                .function_query
+                .matches_decl(node, self.counts.entry(key).or_default())
             && node.function.body.is_some()
         {
-            if let Some(body) = node.function.body.as_mut() {
-                self.insert_tracing(body);
-            }
+            self.insert_tracing(&mut node.function, &name, None);
+        } else {
+            self.trace_analyzed_or_count(&mut node.function, None);
         }
-        false
+        self.matches_nested_functions()
+    }
+
+    pub fn visit_mut_fn_expr(&mut self, node: &mut FnExpr) -> bool {
+        !self.trace_analyzed_or_count(&mut node.function, node.ident.as_ref())
     }
 
     pub fn visit_mut_var_decl(&mut self, node: &mut VarDecl) -> bool {
         let mut traced = false;
         for decl in &mut node.decls {
+            if let (Pat::Ident(name), Some(init)) = (&decl.name, &decl.init) {
+                if let Some(class) = self.prototype_of(init) {
+                    self.prototype_aliases.insert(name.id.sym.clone(), class);
+                }
+            }
             if let Some(init) = &mut decl.init {
                 if let Some(func_expr) = init.as_mut_fn_expr() {
                     if let Pat::Ident(name) = &decl.name {
@@ -227,69 +1113,108 @@ impl Instrumentation {
     }
 
     pub fn visit_mut_class_decl(&mut self, node: &mut ClassDecl) -> bool {
-        self.is_correct_class = self
-            .config
-            .function_query
-            .class_name()
-            .is_none_or(|class| node.ident.sym.as_ref() == class);
+        self.enter_class(&node.ident.sym);
+        self.is_derived_class = node.class.super_class.is_some();
+        // Ambient classes have no code to run.
+        if !node.declare {
+            self.synthesize_constructor(&mut node.class);
+        }
         true
     }
 
     pub fn visit_mut_class_method(&mut self, node: &mut ClassMethod) -> bool {
+        let hoisted = self.hoisted.len();
+        let recurse = self.trace_class_method(node);
+        // Class bodies are strict mode code, but the statement a method's body is hoisted next
+        // to may not be.
+        for stmt in &mut self.hoisted[hoisted..] {
+            if let Some(body) = stmt
+                .as_mut_decl()
+                .and_then(|decl| decl.as_mut_fn_decl())
+                .and_then(|decl| decl.function.body.as_mut())
+            {
+                if !has_use_strict(body) {
+                    body.stmts.insert(0, quote!("'use strict';" as Stmt));
+                }
+            }
+        }
+        recurse
+    }
+
+    fn trace_class_method(&mut self, node: &mut ClassMethod) -> bool {
+        // Methods matched by export path, position or selector are found through the analysis,
+        // whichever class they're declared in.
+        if self.trace_analyzed_or_count(&mut node.function, None) {
+            return true;
+        }
+
         let name = match &node.key {
             PropName::Ident(ident) => ident.sym.clone(),
             _ => return false,
         };
 
-        // Only increment count when class matches
-        if !self.is_correct_class {
-            return true;
-        }
-
-        if self
-            .config
-            .function_query
-            .matches_method(&node.function, &mut self.count, name.as_ref())
-            && node.function.body.is_some()
-        {
-            if let Some(body) = node.function.body.as_mut() {
-                self.insert_tracing(body);
-            }
-        }
+        self.trace_method_or_count(&mut node.function, &name, None);
         true
     }
 
     pub fn visit_mut_constructor(&mut self, node: &mut Constructor) -> bool {
-        if !self.is_correct_class || self.config.function_query.name() != "constructor" {
-            return false;
-        }
-
-        if self.count == self.config.function_query.index() && node.body.is_some() {
-            if let Some(body) = node.body.as_mut() {
-                self.insert_constructor_tracing(body);
-            }
-        } else {
-            self.count += 1;
-        }
+        let params = ParamShape::of_constructor(node);
+        let pats: Vec<Pat> = node
+            .params
+            .iter()
+            .map(|param| match param {
+                ParamOrTsParamProp::Param(param) => param.pat.clone(),
+                ParamOrTsParamProp::TsParamProp(prop) => match &prop.param {
+                    TsParamPropParam::Ident(ident) => Pat::Ident(ident.clone()),
+                    TsParamPropParam::Assign(assign) => Pat::Assign(assign.clone()),
+                },
+            })
+            .collect();
+        self.trace_constructor_or_count(node.span, &params, &pats, node.body.as_mut());
         false
     }
 
     pub fn visit_mut_method_prop(&mut self, node: &mut MethodProp) -> bool {
+        if self.trace_analyzed_or_count(&mut node.function, None) {
+            return self.matches_nested_functions();
+        }
+
         let name = match &node.key {
             PropName::Ident(ident) => ident.sym.clone(),
             _ => return false,
         };
-        if self
-            .config
-            .function_query
-            .matches_method(&node.function, &mut self.count, name.as_ref())
+        let key = self.count_key(&name);
+        if self.in_scope(node.function.span)
+            && self.function_query.matches_method(
+                &node.function,
+                self.counts.entry(key).or_default(),
+                name.as_ref(),
+            )
             && node.function.body.is_some()
         {
-            if let Some(body) = node.function.body.as_mut() {
-                self.insert_tracing(body);
+            self.insert_tracing(&mut node.function, &name, None);
+        }
+        self.matches_nested_functions()
+    }
+
+    pub fn visit_mut_call_expr(&mut self, node: &mut CallExpr) -> bool {
+        // Babel defines the instance and static methods of classes by passing arrays of
+        // descriptors to `_createClass`.
+        let Some(class) = babel_created_class(node) else {
+            return true;
+        };
+        self.enter_class(&class);
+        for arg in node.args.iter_mut().skip(1) {
+            let Expr::Array(array) = &mut *arg.expr else {
+                continue;
+            };
+            for elem in array.elems.iter_mut().flatten() {
+                if let Expr::Object(descriptor) = &mut *elem.expr {
+                    self.trace_descriptor_or_count(descriptor);
+                }
             }
         }
-        false
+        true
     }
 
     pub fn visit_mut_assign_expr(&mut self, node: &mut AssignExpr) -> bool {
@@ -313,7 +1238,23 @@ impl Instrumentation {
                     }
                     SimpleAssignTarget::Member(member) => {
                         if let MemberProp::Ident(ident) = &member.prop {
-                            traced = self.trace_expr_or_count(func_expr, &ident.sym);
+                            // Methods assigned to a prototype or class, as in Babel's loose
+                            // mode, are methods of the class, but can still be matched as
+                            // expressions.
+                            let class = self
+                                .prototype_of(&member.obj)
+                                .or_else(|| self.static_class_of(&member.obj));
+                            if let Some(class) = class {
+                                self.enter_class(&class);
+                                traced = self.trace_method_or_count(
+                                    &mut func_expr.function,
+                                    &ident.sym,
+                                    func_expr.ident.as_ref(),
+                                );
+                            }
+                            if !traced {
+                                traced = self.trace_expr_or_count(func_expr, &ident.sym);
+                            }
                         }
                     }
                     _ => {}
@@ -324,6 +1265,181 @@ impl Instrumentation {
     }
 }
 
+/// How the tracing code of a constructor can get at the object being constructed.
+#[derive(Debug, Clone, Copy)]
+enum ConstructorKind {
+    /// The constructor of a class that doesn't extend another, where `this` is always
+    /// initialized.
+    Base,
+    /// The constructor of a derived class, where `this` is only initialized once `super()` has
+    /// returned.
+    Derived,
+    /// A plain function, which is only traced when it's called with `new`.
+    Function,
+}
+
+/// Rewrites the `super(...)` calls of a derived class's constructor to store what they return,
+/// the newly initialized `this`, as the `self` of the tracing context, as soon as they return.
+/// Calls within arrow functions are rewritten as well, since they share the constructor's
+/// `this`, but those within other functions or nested classes are left alone.
+struct SuperCallRewriter {
+    ctx: Ident,
+}
+
+impl VisitMut for SuperCallRewriter {
+    fn visit_mut_function(&mut self, _node: &mut Function) {}
+
+    fn visit_mut_class(&mut self, _node: &mut Class) {}
+
+    fn visit_mut_expr(&mut self, node: &mut Expr) {
+        node.visit_mut_children_with(self);
+        if let Expr::Call(call) = node {
+            if matches!(call.callee, Callee::Super(_)) {
+                *node = *quote_expr!(
+                    "$ctx.self = $call",
+                    ctx = self.ctx.clone(),
+                    call: Expr = node.take(),
+                );
+            }
+        }
+    }
+}
+
+/// Whether the body of a function keeps its meaning when it's moved into a function declared
+/// next to it, as with [`Codegen::Hoisted`]. Its parameters have to be plain names, so they can
+/// be declared by both functions without evaluating defaults or patterns twice. Nor may the body
+/// refer to the function itself, through `own_name` or `arguments.callee`, since the function it
+/// would be moved into is another.
+fn can_hoist(func: &Function, own_name: Option<&Ident>) -> bool {
+    let Some(body) = &func.body else {
+        return false;
+    };
+    if !func.params.iter().all(|param| param.pat.is_ident()) {
+        return false;
+    }
+    let mut blockers = HoistBlockers {
+        own_name: own_name.map(|ident| ident.sym.clone()),
+        ..HoistBlockers::default()
+    };
+    body.visit_with(&mut blockers);
+    !blockers.found
+}
+
+/// The source map of the file being visited, which doesn't implement [`Debug`] itself.
+#[derive(Clone)]
+struct SourceMap(Lrc<SourceMapperDyn>);
+
+impl std::fmt::Debug for SourceMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SourceMap")
+    }
+}
+
+/// A `key: value` property of an object literal.
+fn key_value(key: &str, value: Expr) -> PropOrSpread {
+    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+        key: PropName::Ident(IdentName::new(key.into(), Span::default())),
+        value: Box::new(value),
+    })))
+}
+
+/// The statements of an `if ($active) { ... }` block of the code wrapping a constructor.
+fn if_active(stmt: &mut Stmt) -> Option<&mut Vec<Stmt>> {
+    match &mut *stmt.as_mut_if_stmt()?.cons {
+        Stmt::Block(block) => Some(&mut block.stmts),
+        _ => None,
+    }
+}
+
+/// Splits the directive prologue, such as `'use strict'` or `'use asm'`, off the start of the
+/// statements of a function's body. Directives only apply at the very start of the function
+/// they're written in, so they have to stay there when the rest of the body is moved elsewhere,
+/// which is also where they're allowed by the function's parameters.
+fn take_directives(stmts: &mut Vec<Stmt>) -> Vec<Stmt> {
+    let rest = stmts.split_off(directive_prologue(stmts).len());
+    std::mem::replace(stmts, rest)
+}
+
+/// The directive prologue at the start of the statements of a function's body, which is left in
+/// place. See [`take_directives`].
+fn directive_prologue(stmts: &[Stmt]) -> &[Stmt] {
+    let count = stmts
+        .iter()
+        .take_while(|stmt| directive(stmt).is_some())
+        .count();
+    &stmts[..count]
+}
+
+/// The value of a statement that's a directive if it's at the start of a function.
+fn directive(stmt: &Stmt) -> Option<&str> {
+    match &*stmt.as_expr()?.expr {
+        Expr::Lit(Lit::Str(directive)) => Some(&directive.value),
+        _ => None,
+    }
+}
+
+/// Whether a block starts with a `'use strict'` directive.
+fn has_use_strict(block: &BlockStmt) -> bool {
+    block
+        .stmts
+        .iter()
+        .map_while(directive)
+        .any(|directive| directive == "use strict")
+}
+
+/// Finds what only has its meaning within the function or class it's written in: `super`,
+/// `new.target` and `arguments.callee` outside of nested functions, and private names and the
+/// function's own name anywhere.
+#[derive(Default)]
+struct HoistBlockers {
+    function_depth: usize,
+    own_name: Option<Atom>,
+    found: bool,
+}
+
+impl Visit for HoistBlockers {
+    fn visit_function(&mut self, node: &Function) {
+        self.function_depth += 1;
+        node.visit_children_with(self);
+        self.function_depth -= 1;
+    }
+
+    fn visit_constructor(&mut self, node: &Constructor) {
+        self.function_depth += 1;
+        node.visit_children_with(self);
+        self.function_depth -= 1;
+    }
+
+    fn visit_super(&mut self, _node: &Super) {
+        self.found |= self.function_depth == 0;
+    }
+
+    fn visit_meta_prop_expr(&mut self, node: &MetaPropExpr) {
+        self.found |= self.function_depth == 0 && node.kind == MetaPropKind::NewTarget;
+    }
+
+    fn visit_private_name(&mut self, _node: &PrivateName) {
+        self.found = true;
+    }
+
+    fn visit_ident(&mut self, node: &Ident) {
+        self.found |= self.own_name.as_ref() == Some(&node.sym);
+    }
+
+    fn visit_member_expr(&mut self, node: &MemberExpr) {
+        let is_callee = node
+            .obj
+            .as_ident()
+            .is_some_and(|obj| obj.sym == "arguments")
+            && node
+                .prop
+                .as_ident()
+                .is_some_and(|prop| prop.sym == "callee");
+        self.found |= self.function_depth == 0 && is_callee;
+        node.visit_children_with(self);
+    }
+}
+
 /// If the script starts with a "use strict" directive, we need to skip it when inserting there
 #[must_use]
 pub fn get_script_start_index(script: &Script) -> usize {
@@ -336,3 +1452,28 @@ pub fn get_script_start_index(script: &Script) -> usize {
     }
     0
 }
+
+/// Strips the TypeScript annotations off parameters, which aren't valid in the assignment targets
+/// they are turned into when rebinding parameters.
+struct StripTypeAnnotations;
+
+impl VisitMut for StripTypeAnnotations {
+    fn visit_mut_opt_ts_type_ann(&mut self, node: &mut Option<Box<TsTypeAnn>>) {
+        *node = None;
+    }
+
+    fn visit_mut_binding_ident(&mut self, node: &mut BindingIdent) {
+        node.id.optional = false;
+        node.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_array_pat(&mut self, node: &mut ArrayPat) {
+        node.optional = false;
+        node.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_object_pat(&mut self, node: &mut ObjectPat) {
+        node.optional = false;
+        node.visit_mut_children_with(self);
+    }
+}
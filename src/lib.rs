@@ -18,29 +18,55 @@
  * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
  * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
  **/
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use swc_core::{
+    common::{errors::SourceMapperDyn, sync::Lrc},
     ecma::{
         ast::{
-            AssignExpr, ClassDecl, ClassMethod, Constructor, FnDecl, MethodProp, Module, Script,
-            Str, VarDecl,
+            ArrowExpr, AssignExpr, CallExpr, ClassDecl, ClassExpr, ClassMethod, Constructor, Decl,
+            FnDecl, FnExpr, ForInStmt, ForOfStmt, ForStmt, Function, MethodProp, Module,
+            ModuleItem, ParamOrTsParamProp, Program, Script, Stmt, VarDecl, WithStmt,
         },
         visit::{VisitMut, VisitMutWith},
     },
-    quote,
 };
 
+mod analysis;
+use analysis::{Analysis, AnalysisOptions};
+
 mod error;
+pub use error::OrchestrionError;
+
+mod backend;
+pub use backend::TracingBackend;
 
 mod config;
 pub use config::*;
 
+mod context_builder;
+pub use context_builder::ContextBuilder;
+
+mod template;
+pub use template::CodeTemplate;
+
 mod instrumentation;
 pub use instrumentation::*;
 
 mod function_query;
 pub use function_query::*;
 
+mod fingerprint;
+pub use fingerprint::*;
+
+mod helpers;
+
+mod selector;
+pub use selector::Selector;
+
+mod syntax;
+pub use syntax::SourceSyntax;
+
 /// This struct is responsible for managing all instrumentations. It's created from a YAML string
 /// via the [`FromStr`] trait. See tests for examples, but by-and-large this just means you can
 /// call `.parse()` on a YAML string to get an `Instrumentor` instance, if it's valid.
@@ -49,18 +75,41 @@ pub use function_query::*;
 pub struct Instrumentor {
     instrumentations: Vec<Instrumentation>,
     dc_module: String,
+    syntax: Option<SourceSyntax>,
+    backend: TracingBackend,
 }
 
 impl Instrumentor {
-    pub fn new(config: Config) -> Self {
-        Self {
+    /// Creates an `Instrumentor` for the given configuration, once it's been validated.
+    /// # Errors
+    /// Returns an error if the configuration is invalid. See [`Config::validate`].
+    pub fn new(config: Config) -> Result<Self, OrchestrionError> {
+        config.validate()?;
+        Ok(Self {
             instrumentations: config
                 .instrumentations
                 .into_iter()
-                .map(Instrumentation::new)
+                .flat_map(|config| {
+                    config
+                        .function_queries
+                        .clone()
+                        .into_iter()
+                        .map(move |query| Instrumentation::for_query(config.clone(), query))
+                })
                 .collect(),
             dc_module: config.dc_module,
-        }
+            syntax: config.syntax,
+            backend: config.backend,
+        })
+    }
+
+    /// The syntax to parse a file with before instrumenting it, which is the one configured with
+    /// [`Config::with_syntax`], or otherwise detected from the file's extension and contents
+    /// with [`SourceSyntax::detect`].
+    #[must_use]
+    pub fn source_syntax(&self, file_path: &Path, contents: &str) -> SourceSyntax {
+        self.syntax
+            .unwrap_or_else(|| SourceSyntax::detect(file_path, contents))
     }
 
     /// For a given module name, version, and file path within the module, return all
@@ -71,42 +120,266 @@ impl Instrumentor {
         version: &'a str,
         file_path: &'a PathBuf,
     ) -> InstrumentationVisitor<'a> {
-        let instrumentations = self
+        let mut instrumentations: Vec<_> = self
             .instrumentations
             .iter_mut()
-            .filter(|instr| instr.matches(module_name, version, file_path));
+            .filter(|instr| instr.matches(module_name, version, file_path))
+            .collect();
+        for instr in &mut instrumentations {
+            instr.set_module_version(version);
+        }
 
-        InstrumentationVisitor::new(instrumentations, self.dc_module.as_ref())
+        InstrumentationVisitor::new(
+            instrumentations.into_iter(),
+            self.dc_module.as_ref(),
+            &self.backend,
+        )
     }
 }
 
-#[derive(Debug)]
+/// Which alternative of a [`FunctionQuery::FirstOf`] query was applied to a file.
+#[derive(Debug, Clone)]
+pub struct AppliedAlternative {
+    /// The channel name of the instrumentation, before any placeholders are filled in.
+    pub channel_name: String,
+    /// The position of the applied alternative in the list of alternatives.
+    pub index: usize,
+    pub query: FunctionQuery,
+}
+
 pub struct InstrumentationVisitor<'a> {
     instrumentations: Vec<&'a mut Instrumentation>,
+    /// Whether each instrumentation visits the node being visited, which it doesn't within the
+    /// nodes it chose not to recurse into.
+    active: Vec<bool>,
     dc_module: &'a str,
+    backend: &'a TracingBackend,
+    source_map: Option<Lrc<SourceMapperDyn>>,
+    applied_alternatives: Vec<AppliedAlternative>,
+    /// Whether a declaration can be added right before the statement being visited, in the same
+    /// scope as the node being visited.
+    can_hoist: bool,
+}
+
+impl std::fmt::Debug for InstrumentationVisitor<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentationVisitor")
+            .field("instrumentations", &self.instrumentations)
+            .field("dc_module", &self.dc_module)
+            .field("backend", &self.backend)
+            .field("source_map", &self.source_map.is_some())
+            .field("applied_alternatives", &self.applied_alternatives)
+            .finish()
+    }
 }
 
 impl<'a> InstrumentationVisitor<'a> {
-    fn new<I>(instrumentations: I, dc_module: &'a str) -> Self
+    fn new<I>(instrumentations: I, dc_module: &'a str, backend: &'a TracingBackend) -> Self
     where
         I: Iterator<Item = &'a mut Instrumentation> + 'a,
     {
+        let instrumentations: Vec<_> = instrumentations.collect();
         Self {
-            instrumentations: instrumentations.collect(),
+            active: vec![true; instrumentations.len()],
+            instrumentations,
             dc_module,
+            backend,
+            source_map: None,
+            applied_alternatives: vec![],
+            can_hoist: false,
+        }
+    }
+
+    /// The alternatives applied to the files visited so far, for instrumentations with
+    /// [`FunctionQuery::FirstOf`] queries. Instrumentations where no alternative matched
+    /// anything in a file aren't listed for it.
+    #[must_use]
+    pub fn applied_alternatives(&self) -> &[AppliedAlternative] {
+        &self.applied_alternatives
+    }
+
+    /// Chooses the first alternative that matches anything in the program, for every
+    /// instrumentation that has alternatives. Each alternative is tried by a probe, which visits
+    /// the program without changing it.
+    fn resolve_alternatives<N>(&mut self, node: &mut N)
+    where
+        N: for<'b> VisitMutWith<InstrumentationVisitor<'b>>,
+    {
+        for instr in &mut self.instrumentations {
+            let Some(alternatives) = instr.alternatives().map(<[FunctionQuery]>::to_vec) else {
+                continue;
+            };
+            let applied = alternatives
+                .into_iter()
+                .enumerate()
+                .find(|(_, alternative)| {
+                    let mut probe = instr.probe(alternative);
+                    let mut visitor = InstrumentationVisitor::new(
+                        std::iter::once(&mut probe),
+                        self.dc_module,
+                        self.backend,
+                    );
+                    node.visit_mut_children_with(&mut visitor);
+                    probe.traced_any()
+                });
+            if let Some((index, alternative)) = applied {
+                self.applied_alternatives.push(AppliedAlternative {
+                    channel_name: instr.channel_name_template().to_string(),
+                    index,
+                    query: alternative.clone(),
+                });
+                instr.apply_alternative(alternative);
+            }
+        }
+    }
+
+    /// Sets the source map of the file being visited, which is needed to match functions by
+    /// their location, as with [`FunctionQuery::Location`].
+    pub fn set_source_map(&mut self, source_map: Lrc<SourceMapperDyn>) {
+        for instr in &mut self.instrumentations {
+            instr.set_source_map(source_map.clone());
+        }
+        self.source_map = Some(source_map);
+    }
+
+    /// Whether the program needs `tr_ch_apm_tracingChannel`, which only instrumentations that
+    /// don't use a [`CodeTemplate`] publish with.
+    fn needs_prologue(&self) -> bool {
+        !self
+            .instrumentations
+            .iter()
+            .all(|instr| instr.uses_template())
+    }
+
+    /// Declares each channel used in the program once, right after the import of
+    /// `tracingChannel`, even if several instrumentations publish on it, followed by the
+    /// metadata of the traced functions. This also resets the instrumentations for the next
+    /// program.
+    fn channel_declarations(&mut self) -> Vec<Stmt> {
+        let mut declared: Vec<&str> = vec![];
+        let mut declarations = vec![];
+        for instr in &self.instrumentations {
+            if instr.uses_template() {
+                continue;
+            }
+            for channel in instr.channels() {
+                if !declared.contains(&channel.as_str()) {
+                    declared.push(channel);
+                    declarations.push(instr.create_tracing_channel(channel));
+                }
+            }
+        }
+        for instr in &mut self.instrumentations {
+            declarations.append(&mut instr.take_sites());
+            instr.reset();
+        }
+        declarations
+    }
+
+    /// Visits each statement of a list, and declares the functions hoisted out of the functions
+    /// traced within a statement right before it, in the same scope. See [`Codegen::Hoisted`].
+    fn visit_mut_statement_list<T>(&mut self, items: &mut Vec<T>, wrap: impl Fn(Stmt) -> T)
+    where
+        T: for<'b> VisitMutWith<InstrumentationVisitor<'b>>,
+    {
+        let can_hoist = std::mem::replace(&mut self.can_hoist, true);
+        let mut i = 0;
+        while i < items.len() {
+            let hoisted_before: Vec<usize> = self
+                .instrumentations
+                .iter()
+                .map(|instr| instr.hoisted_len())
+                .collect();
+            items[i].visit_mut_with(self);
+            let mut hoisted = vec![];
+            for (instr, from) in self.instrumentations.iter_mut().zip(hoisted_before) {
+                hoisted.extend(instr.take_hoisted(from));
+            }
+            // Functions nested within the hoisted bodies can still be matched.
+            for stmt in &mut hoisted {
+                if let Stmt::Decl(Decl::Fn(decl)) = stmt {
+                    decl.function.body.visit_mut_with(self);
+                }
+            }
+            let count = hoisted.len();
+            items.splice(i..i, hoisted.into_iter().map(&wrap));
+            i += count + 1;
+        }
+        self.can_hoist = can_hoist;
+    }
+
+    /// Visits a node whose children are in a scope of their own, where declarations can't be
+    /// hoisted to the statement list around them, such as the parameters of a function or the
+    /// head of a `for` loop.
+    fn visit_mut_in_scope<N>(&mut self, node: &mut N)
+    where
+        N: for<'b> VisitMutWith<InstrumentationVisitor<'b>>,
+    {
+        let can_hoist = std::mem::replace(&mut self.can_hoist, false);
+        node.visit_mut_children_with(self);
+        self.can_hoist = can_hoist;
+    }
+
+    /// Analyzes the program up front, but only if some instrumentation needs it, since it
+    /// involves copying the whole program.
+    fn analyze(&mut self, program: impl FnOnce() -> Program) {
+        if !self
+            .instrumentations
+            .iter()
+            .any(|instr| instr.needs_analysis())
+        {
+            return;
+        }
+        let options = AnalysisOptions {
+            source_map: self.source_map.as_deref(),
+            fingerprints: self
+                .instrumentations
+                .iter()
+                .any(|instr| instr.needs_fingerprints()),
+            selectors: self
+                .instrumentations
+                .iter()
+                .flat_map(|instr| instr.selectors().into_iter().cloned())
+                .collect(),
+        };
+        let analysis = Rc::new(Analysis::new(program(), &options));
+        for instr in &mut self.instrumentations {
+            instr.set_analysis(analysis.clone());
         }
     }
 }
 
+// Each instrumentation decides whether it visits the children of a node. Those that don't are
+// paused while the children are visited for the others, so they neither count nor trace the
+// functions within.
 macro_rules! visit_with_all {
     ($self:expr, $method:ident, $item:expr) => {
         let mut recurse = false;
-        for instr in &mut $self.instrumentations {
-            let needs_recurse = instr.$method($item);
-            recurse = recurse || needs_recurse;
+        let mut paused = vec![];
+        for (i, (instr, active)) in $self
+            .instrumentations
+            .iter_mut()
+            .zip(&$self.active)
+            .enumerate()
+        {
+            if !*active {
+                continue;
+            }
+            instr.set_can_hoist($self.can_hoist);
+            if instr.$method($item) {
+                recurse = true;
+            } else {
+                paused.push(i);
+            }
         }
         if recurse {
+            for &i in &paused {
+                $self.active[i] = false;
+            }
             $item.visit_mut_children_with($self);
+            for &i in &paused {
+                $self.active[i] = true;
+            }
         }
     };
 }
@@ -121,37 +394,89 @@ macro_rules! visit_with_all_fn {
 
 impl VisitMut for InstrumentationVisitor<'_> {
     fn visit_mut_module(&mut self, item: &mut Module) {
-        let mut line = quote!(
-            "import { tracingChannel as tr_ch_apm_tracingChannel } from 'dc';" as ModuleItem,
-        );
-        if let Some(module_decl) = line.as_mut_module_decl() {
-            if let Some(import) = module_decl.as_mut_import() {
-                import.src = Box::new(Str::from(self.dc_module));
-                item.body.insert(0, line);
-            }
-        }
+        let prologue = if self.needs_prologue() {
+            self.backend.module_prologue(self.dc_module)
+        } else {
+            vec![]
+        };
+        let end = prologue.len();
+        item.body.splice(0..0, prologue);
+        self.analyze(|| Program::Module(item.clone()));
+        self.resolve_alternatives(item);
         visit_with_all!(self, visit_mut_module, item);
-        for instr in &mut self.instrumentations {
-            instr.reset();
-        }
+        let declarations = self.channel_declarations();
+        item.body
+            .splice(end..end, declarations.into_iter().map(ModuleItem::Stmt));
     }
 
     fn visit_mut_script(&mut self, item: &mut Script) {
-        let import = quote!(
-            "const { tracingChannel: tr_ch_apm_tracingChannel } = require($dc);" as Stmt,
-            dc: Expr = self.dc_module.into(),
-        );
-        item.body.insert(get_script_start_index(item), import);
+        let prologue = if self.needs_prologue() {
+            self.backend.script_prologue(self.dc_module)
+        } else {
+            vec![]
+        };
+        let prologue_len = prologue.len();
+        let start_index = get_script_start_index(item);
+        item.body.splice(start_index..start_index, prologue);
+        self.analyze(|| Program::Script(item.clone()));
+        self.resolve_alternatives(item);
         visit_with_all!(self, visit_mut_script, item);
-        for instr in &mut self.instrumentations {
-            instr.reset();
+        let start_index = get_script_start_index(item) + prologue_len;
+        let declarations = self.channel_declarations();
+        item.body.splice(start_index..start_index, declarations);
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        self.visit_mut_statement_list(stmts, |stmt| stmt);
+    }
+
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        self.visit_mut_statement_list(items, ModuleItem::Stmt);
+    }
+
+    fn visit_mut_function(&mut self, node: &mut Function) {
+        self.visit_mut_in_scope(node);
+    }
+
+    fn visit_mut_arrow_expr(&mut self, node: &mut ArrowExpr) {
+        self.visit_mut_in_scope(node);
+    }
+
+    fn visit_mut_param_or_ts_param_props(&mut self, node: &mut Vec<ParamOrTsParamProp>) {
+        self.visit_mut_in_scope(node);
+    }
+
+    fn visit_mut_for_stmt(&mut self, node: &mut ForStmt) {
+        self.visit_mut_in_scope(node);
+    }
+
+    fn visit_mut_for_in_stmt(&mut self, node: &mut ForInStmt) {
+        self.visit_mut_in_scope(node);
+    }
+
+    fn visit_mut_for_of_stmt(&mut self, node: &mut ForOfStmt) {
+        self.visit_mut_in_scope(node);
+    }
+
+    fn visit_mut_with_stmt(&mut self, node: &mut WithStmt) {
+        self.visit_mut_in_scope(node);
+    }
+
+    fn visit_mut_class_expr(&mut self, node: &mut ClassExpr) {
+        // The name of a class expression is only in scope within the class.
+        if node.ident.is_some() {
+            self.visit_mut_in_scope(node);
+        } else {
+            node.visit_mut_children_with(self);
         }
     }
 
     visit_with_all_fn!(visit_mut_fn_decl, FnDecl);
+    visit_with_all_fn!(visit_mut_fn_expr, FnExpr);
     visit_with_all_fn!(visit_mut_var_decl, VarDecl);
     visit_with_all_fn!(visit_mut_method_prop, MethodProp);
     visit_with_all_fn!(visit_mut_assign_expr, AssignExpr);
+    visit_with_all_fn!(visit_mut_call_expr, CallExpr);
     visit_with_all_fn!(visit_mut_class_decl, ClassDecl);
     visit_with_all_fn!(visit_mut_class_method, ClassMethod);
     visit_with_all_fn!(visit_mut_constructor, Constructor);
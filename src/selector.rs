@@ -0,0 +1,468 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
+ **/
+use crate::analysis::{member_expr_segments, member_segments, prop_name_str};
+use crate::error::OrchestrionError;
+use crate::function_query::{name_matches, validate_name};
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use swc_core::common::BytePos;
+use swc_core::ecma::ast::{
+    AssignOp, AssignTarget, Callee, ClassMethod, Function, Ident, MethodKind, Pat, Program,
+    SimpleAssignTarget,
+};
+use swc_ecma_visit::{
+    fields::{ClassMethodField, FnDeclField, FnExprField, MethodPropField, PrivateMethodField},
+    AstNodePath, AstParentNodeRef, VisitAstPath, VisitWithAstPath,
+};
+
+/// The kinds of node a selector can name. Every other node in the AST is skipped over when
+/// matching, so `Class > Method` matches a method of a class even though the method is nested in
+/// a few other nodes.
+const KINDS: &[&str] = &[
+    "*", "Class", "Method", "Function", "Arrow", "Assign", "Var", "Prop", "Call", "Export",
+];
+
+/// The kinds a selector can end with, since only these can be traced.
+const TARGET_KINDS: &[&str] = &["*", "Method", "Function"];
+
+const ATTRIBUTES: &[&str] = &[
+    "name",
+    "target",
+    "callee",
+    "kind",
+    "async",
+    "generator",
+    "static",
+    "default",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    /// `A B`: `B` is anywhere inside `A`.
+    Descendant,
+    /// `A > B`: `A` is the closest node around `B` that a selector can name.
+    Child,
+}
+
+#[derive(Debug, Clone)]
+struct Attribute {
+    name: String,
+    value: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Compound {
+    kind: String,
+    attributes: Vec<Attribute>,
+}
+
+/// A node of the AST as seen by a selector, with the attributes it can be matched on.
+#[derive(Debug)]
+struct SelectorNode {
+    kind: &'static str,
+    attributes: HashMap<&'static str, String>,
+}
+
+impl SelectorNode {
+    fn new(kind: &'static str) -> Self {
+        SelectorNode {
+            kind,
+            attributes: HashMap::new(),
+        }
+    }
+
+    fn with(mut self, name: &'static str, value: Option<String>) -> Self {
+        if let Some(value) = value {
+            self.attributes.insert(name, value);
+        }
+        self
+    }
+
+    fn flag(self, name: &'static str, set: bool) -> Self {
+        self.with(name, set.then(|| "true".to_string()))
+    }
+
+    fn function(self, func: &Function) -> Self {
+        self.flag("async", func.is_async)
+            .flag("generator", func.is_generator)
+    }
+
+    fn method(method: &ClassMethod) -> Self {
+        SelectorNode::new("Method")
+            .with("name", prop_name_str(&method.key))
+            .with("kind", Some(method_kind(method.kind).to_string()))
+            .flag("static", method.is_static)
+            .function(&method.function)
+    }
+}
+
+fn method_kind(kind: MethodKind) -> &'static str {
+    match kind {
+        MethodKind::Method => "method",
+        MethodKind::Getter => "getter",
+        MethodKind::Setter => "setter",
+    }
+}
+
+/// Prints a dotted path like `module.exports.fetch` from the segments of a member expression.
+fn dotted_path(segments: Option<(&Ident, Vec<String>)>) -> Option<String> {
+    let (root, segments) = segments?;
+    Some(
+        std::iter::once(root.sym.to_string())
+            .chain(segments)
+            .collect::<Vec<_>>()
+            .join("."),
+    )
+}
+
+/// Converts a node on the path to a function into what a selector sees of it, if anything. The
+/// function itself is represented by the method or function node around it.
+fn selector_node(node: &AstParentNodeRef) -> Option<SelectorNode> {
+    let node = match node {
+        AstParentNodeRef::ClassDecl(decl, _) => {
+            SelectorNode::new("Class").with("name", Some(decl.ident.sym.to_string()))
+        }
+        AstParentNodeRef::ClassExpr(expr, _) => SelectorNode::new("Class").with(
+            "name",
+            expr.ident.as_ref().map(|ident| ident.sym.to_string()),
+        ),
+        AstParentNodeRef::ClassMethod(method, _) => SelectorNode::method(method),
+        AstParentNodeRef::PrivateMethod(method, _) => SelectorNode::new("Method")
+            .with("name", Some(format!("#{}", method.key.name)))
+            .with("kind", Some(method_kind(method.kind).to_string()))
+            .flag("static", method.is_static)
+            .function(&method.function),
+        AstParentNodeRef::MethodProp(method, _) => SelectorNode::new("Method")
+            .with("name", prop_name_str(&method.key))
+            .with("kind", Some("method".to_string()))
+            .function(&method.function),
+        AstParentNodeRef::FnDecl(decl, _) => SelectorNode::new("Function")
+            .with("name", Some(decl.ident.sym.to_string()))
+            .function(&decl.function),
+        AstParentNodeRef::FnExpr(expr, _) => SelectorNode::new("Function")
+            .with(
+                "name",
+                expr.ident.as_ref().map(|ident| ident.sym.to_string()),
+            )
+            .function(&expr.function),
+        AstParentNodeRef::ArrowExpr(arrow, _) => SelectorNode::new("Arrow")
+            .flag("async", arrow.is_async)
+            .flag("generator", arrow.is_generator),
+        AstParentNodeRef::AssignExpr(assign, _) => {
+            let target = match &assign.left {
+                AssignTarget::Simple(SimpleAssignTarget::Ident(ident)) => {
+                    Some(ident.sym.to_string())
+                }
+                AssignTarget::Simple(SimpleAssignTarget::Member(member)) => {
+                    dotted_path(member_expr_segments(member))
+                }
+                _ => None,
+            };
+            SelectorNode::new("Assign")
+                .with("target", target.filter(|_| assign.op == AssignOp::Assign))
+        }
+        AstParentNodeRef::VarDeclarator(decl, _) => {
+            let name = match &decl.name {
+                Pat::Ident(ident) => Some(ident.id.sym.to_string()),
+                _ => None,
+            };
+            SelectorNode::new("Var").with("name", name)
+        }
+        AstParentNodeRef::KeyValueProp(prop, _) => {
+            SelectorNode::new("Prop").with("name", prop_name_str(&prop.key))
+        }
+        AstParentNodeRef::CallExpr(call, _) => {
+            let callee = match &call.callee {
+                Callee::Expr(expr) => dotted_path(member_segments(expr)),
+                _ => None,
+            };
+            SelectorNode::new("Call").with("callee", callee)
+        }
+        AstParentNodeRef::ExportDecl(..) => SelectorNode::new("Export"),
+        AstParentNodeRef::ExportDefaultDecl(..) | AstParentNodeRef::ExportDefaultExpr(..) => {
+            SelectorNode::new("Export").flag("default", true)
+        }
+        _ => return None,
+    };
+    Some(node)
+}
+
+/// Whether a node on the path is the one holding the function being visited, rather than one
+/// further out.
+fn holds_function(node: &AstParentNodeRef) -> bool {
+    matches!(
+        node,
+        AstParentNodeRef::ClassMethod(_, ClassMethodField::Function)
+            | AstParentNodeRef::PrivateMethod(_, PrivateMethodField::Function)
+            | AstParentNodeRef::MethodProp(_, MethodPropField::Function)
+            | AstParentNodeRef::FnDecl(_, FnDeclField::Function)
+            | AstParentNodeRef::FnExpr(_, FnExprField::Function)
+    )
+}
+
+impl Compound {
+    fn matches(&self, node: &SelectorNode) -> bool {
+        (self.kind == "*" || self.kind == node.kind)
+            && self.attributes.iter().all(|attribute| {
+                match (
+                    &attribute.value,
+                    node.attributes.get(attribute.name.as_str()),
+                ) {
+                    (None, value) => value.is_some(),
+                    (Some(pattern), Some(value)) => name_matches(pattern, value),
+                    (Some(_), None) => false,
+                }
+            })
+    }
+}
+
+/// A selector over the syntax tree, in a syntax like CSS selectors, for finding functions by
+/// their surroundings. For example, `Class[name=Undici] > Method[name=fetch][async]` matches the
+/// async `fetch` method of the `Undici` class, and
+/// `Assign[target="module.exports.fetch"] > Function` matches a function assigned to
+/// `module.exports.fetch`.
+///
+/// A selector is a list of node kinds, each followed by any number of attributes in brackets.
+/// Kinds separated by whitespace match nodes nested anywhere inside each other, and kinds
+/// separated by `>` match nodes directly inside each other, ignoring any nodes a selector can't
+/// name. The last kind must be `Method`, `Function` or `*`, and is the function that's traced.
+///
+/// | Kind       | Matches                   | Attributes                                     |
+/// |------------|---------------------------|------------------------------------------------|
+/// | `Class`    | classes                   | `name`                                         |
+/// | `Method`   | class and object methods  | `name`, `kind`, `static`, `async`, `generator` |
+/// | `Function` | non-arrow functions       | `name`, `async`, `generator`                   |
+/// | `Arrow`    | arrow functions           | `async`                                        |
+/// | `Assign`   | assignments               | `target`                                       |
+/// | `Var`      | variable declarations     | `name`                                         |
+/// | `Prop`     | object literal properties | `name`                                         |
+/// | `Call`     | function calls            | `callee`                                       |
+/// | `Export`   | `export` declarations     | `default`                                      |
+/// | `*`        | any of the above          |                                                |
+///
+/// The `target` of an assignment and the `callee` of a call are paths, such as
+/// `module.exports.fetch` and `Object.defineProperty`.
+///
+/// An attribute without a value, like `[async]`, requires the attribute to be present, and one
+/// with a value, like `[name=fetch]`, requires it to match. Values can be quoted, and can be
+/// patterns like the name fields of [`FunctionQuery`].
+///
+/// [`FunctionQuery`]: crate::FunctionQuery
+#[derive(Debug, Clone)]
+pub struct Selector {
+    text: String,
+    compounds: Vec<Compound>,
+    combinators: Vec<Combinator>,
+}
+
+impl Selector {
+    pub(crate) fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Matches the selector against the nodes around a function, outermost first. The last node
+    /// is the function itself.
+    fn matches(&self, nodes: &[SelectorNode]) -> bool {
+        let Some((target, compounds)) = self.compounds.split_last() else {
+            return false;
+        };
+        let Some((node, ancestors)) = nodes.split_last() else {
+            return false;
+        };
+        target.matches(node) && Self::matches_ancestors(compounds, &self.combinators, ancestors)
+    }
+
+    fn matches_ancestors(
+        compounds: &[Compound],
+        combinators: &[Combinator],
+        nodes: &[SelectorNode],
+    ) -> bool {
+        let (Some((compound, compounds)), Some((combinator, combinators))) =
+            (compounds.split_last(), combinators.split_last())
+        else {
+            return true;
+        };
+        match combinator {
+            Combinator::Child => nodes.split_last().is_some_and(|(node, nodes)| {
+                compound.matches(node) && Self::matches_ancestors(compounds, combinators, nodes)
+            }),
+            Combinator::Descendant => (0..nodes.len()).rev().any(|i| {
+                compound.matches(&nodes[i])
+                    && Self::matches_ancestors(compounds, combinators, &nodes[..i])
+            }),
+        }
+    }
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// A cursor over the text of a selector being parsed.
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let start = self.pos;
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+        self.pos > start
+    }
+
+    fn error(&self, message: &str) -> OrchestrionError {
+        format!(
+            "Invalid selector {:?} at {}: {message}",
+            self.input, self.pos
+        )
+        .into()
+    }
+
+    fn word(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '*' | '-'))
+        {
+            self.bump();
+        }
+        self.input[start..self.pos].to_string()
+    }
+
+    fn value(&mut self) -> Result<String, OrchestrionError> {
+        let Some(quote @ ('"' | '\'')) = self.peek() else {
+            let start = self.pos;
+            while self.peek().is_some_and(|c| c != ']' && !c.is_whitespace()) {
+                self.bump();
+            }
+            return Ok(self.input[start..self.pos].to_string());
+        };
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('\\') => value.extend(self.bump()),
+                Some(c) if c == quote => return Ok(value),
+                Some(c) => value.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn attribute(&mut self) -> Result<Attribute, OrchestrionError> {
+        self.skip_whitespace();
+        let name = self.word();
+        if !ATTRIBUTES.contains(&name.as_str()) {
+            return Err(self.error(&format!("unknown attribute {name:?}")));
+        }
+        self.skip_whitespace();
+        let value = if self.peek() == Some('=') {
+            self.bump();
+            self.skip_whitespace();
+            let value = self.value()?;
+            validate_name(&value)?;
+            Some(value)
+        } else {
+            None
+        };
+        self.skip_whitespace();
+        if self.bump() != Some(']') {
+            return Err(self.error("expected ]"));
+        }
+        Ok(Attribute { name, value })
+    }
+
+    fn compound(&mut self) -> Result<Compound, OrchestrionError> {
+        let kind = self.word();
+        if !KINDS.contains(&kind.as_str()) {
+            return Err(self.error(&format!("unknown node kind {kind:?}")));
+        }
+        let mut attributes = vec![];
+        while self.peek() == Some('[') {
+            self.bump();
+            attributes.push(self.attribute()?);
+        }
+        Ok(Compound { kind, attributes })
+    }
+}
+
+impl FromStr for Selector {
+    type Err = OrchestrionError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { input, pos: 0 };
+        let mut compounds = vec![];
+        let mut combinators = vec![];
+        parser.skip_whitespace();
+        loop {
+            compounds.push(parser.compound()?);
+            let spaced = parser.skip_whitespace();
+            match parser.peek() {
+                None => break,
+                Some('>') => {
+                    parser.bump();
+                    parser.skip_whitespace();
+                    combinators.push(Combinator::Child);
+                }
+                Some(_) if spaced => combinators.push(Combinator::Descendant),
+                Some(c) => return Err(parser.error(&format!("unexpected {c:?}"))),
+            }
+        }
+        let target = compounds.last().map(|compound| compound.kind.as_str());
+        if !target.is_some_and(|kind| TARGET_KINDS.contains(&kind)) {
+            return Err(parser.error("selectors must end with Method, Function or *"));
+        }
+        Ok(Selector {
+            text: input.trim().to_string(),
+            compounds,
+            combinators,
+        })
+    }
+}
+
+/// Finds the functions matched by each selector, keyed by the start of their span.
+pub(crate) struct SelectorCollector<'a> {
+    pub(crate) selectors: &'a [Selector],
+    pub(crate) matches: HashMap<BytePos, Vec<String>>,
+}
+
+impl SelectorCollector<'_> {
+    pub(crate) fn collect(&mut self, program: &Program) {
+        program.visit_with_ast_path(self, &mut AstNodePath::default());
+    }
+}
+
+impl VisitAstPath for SelectorCollector<'_> {
+    fn visit_function<'ast: 'r, 'r>(&mut self, node: &'ast Function, path: &mut AstNodePath<'r>) {
+        if !node.span.is_dummy() && path.last().is_some_and(holds_function) {
+            let nodes: Vec<SelectorNode> = path.iter().filter_map(selector_node).collect();
+            for selector in self.selectors {
+                if selector.matches(&nodes) {
+                    self.matches
+                        .entry(node.span.lo)
+                        .or_default()
+                        .push(selector.as_str().to_string());
+                }
+            }
+        }
+        node.visit_children_with_ast_path(self, path);
+    }
+}
@@ -0,0 +1,125 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
+ **/
+use std::path::Path;
+use swc_core::common::{FileName, SourceMap, Span};
+use swc_core::ecma::{
+    ast::{EsVersion, Stmt},
+    visit::{VisitMut, VisitMutWith},
+};
+use swc_ecma_parser::{parse_file_as_script, EsSyntax, Syntax, TsSyntax};
+
+/// The syntax a file is written in, which determines how it has to be parsed before it can be
+/// instrumented. Instrumentation only rewrites the bodies of matched functions, so JSX and type
+/// annotations elsewhere in a file are printed back as they were.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceSyntax {
+    /// JavaScript, including JSX.
+    JavaScript,
+    /// TypeScript without JSX, as in `.ts` files, where `<T>value` is a type assertion.
+    TypeScript,
+    /// TypeScript with JSX, as in `.tsx` files.
+    Tsx,
+    /// JavaScript with Flow type annotations. SWC has no Flow parser, so these files are parsed
+    /// as TypeScript with JSX, which covers the annotations the two have in common, such as
+    /// `function f(x: number): string`, `import type` and generics. Flow-only syntax, such as
+    /// `?string` or exact object types, fails to parse.
+    Flow,
+}
+
+impl SourceSyntax {
+    /// Detects the syntax of a file from its extension, and for JavaScript files, from whether
+    /// they start with a `@flow` pragma comment.
+    #[must_use]
+    pub fn detect(file_path: &Path, contents: &str) -> Self {
+        let file_name = file_path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        if file_name.ends_with(".js.flow") {
+            return SourceSyntax::Flow;
+        }
+        match file_path.extension().and_then(|ext| ext.to_str()) {
+            Some("ts" | "mts" | "cts") => SourceSyntax::TypeScript,
+            Some("tsx") => SourceSyntax::Tsx,
+            _ if has_flow_pragma(contents) => SourceSyntax::Flow,
+            _ => SourceSyntax::JavaScript,
+        }
+    }
+
+    /// The SWC parser syntax for files written in this syntax.
+    #[must_use]
+    pub fn syntax(self) -> Syntax {
+        match self {
+            SourceSyntax::JavaScript => Syntax::Es(EsSyntax {
+                jsx: true,
+                decorators: true,
+                explicit_resource_management: true,
+                import_attributes: true,
+                ..Default::default()
+            }),
+            SourceSyntax::TypeScript | SourceSyntax::Tsx | SourceSyntax::Flow => {
+                Syntax::Typescript(TsSyntax {
+                    tsx: self != SourceSyntax::TypeScript,
+                    decorators: true,
+                    ..Default::default()
+                })
+            }
+        }
+    }
+}
+
+/// Whether the comments at the start of a file, before any code, contain a `@flow` pragma.
+fn has_flow_pragma(contents: &str) -> bool {
+    let mut rest = contents.trim_start();
+    if rest.starts_with("#!") {
+        rest = rest
+            .split_once('\n')
+            .map_or("", |(_, rest)| rest)
+            .trim_start();
+    }
+    loop {
+        let (comment, after) = if let Some(line) = rest.strip_prefix("//") {
+            line.split_once('\n').unwrap_or((line, ""))
+        } else if let Some(block) = rest.strip_prefix("/*") {
+            block.split_once("*/").unwrap_or((block, ""))
+        } else {
+            return false;
+        };
+        if comment.split_whitespace().any(|word| word == "@flow") {
+            return true;
+        }
+        rest = after.trim_start();
+    }
+}
+
+/// Parses a snippet of JavaScript from the configuration as a script, such as an expression to
+/// insert into traced functions. The spans of the snippet are dropped, since they don't point
+/// into the files it's inserted into.
+pub(crate) fn parse_snippet(source: &str) -> Result<Vec<Stmt>, String> {
+    let source_map = SourceMap::default();
+    let file = source_map.new_source_file(FileName::Anon.into(), source.to_string());
+    let mut errors = vec![];
+    let mut script = parse_file_as_script(
+        &file,
+        SourceSyntax::JavaScript.syntax(),
+        EsVersion::latest(),
+        None,
+        &mut errors,
+    )
+    .map_err(|e| e.kind().msg().to_string())?;
+    if let Some(error) = errors.first() {
+        return Err(error.kind().msg().to_string());
+    }
+    script.visit_mut_with(&mut DropSpans);
+    Ok(script.body)
+}
+
+struct DropSpans;
+
+impl VisitMut for DropSpans {
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        *span = Span::default();
+    }
+}
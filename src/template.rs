@@ -0,0 +1,261 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (<https://www.datadoghq.com>/). Copyright 2025 Datadog, Inc.
+ **/
+use crate::error::OrchestrionError;
+use crate::syntax::parse_snippet;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use swc_core::common::Span;
+use swc_core::ecma::{
+    ast::{
+        ArrowExpr, CallExpr, Class, DoWhileStmt, Expr, ForInStmt, ForOfStmt, ForStmt, Function,
+        GetterProp, Ident, IdentName, KeyValueProp, Prop, PropName, SetterProp, Stmt, ThisExpr,
+        WhileStmt,
+    },
+    visit::{Visit, VisitMut, VisitMutWith, VisitWith},
+};
+
+const PLACEHOLDERS: &[&str] = &["$body", "$channel", "$context", "$self", "$kind"];
+
+/// The statements that replace the body of each traced function, instead of the code publishing
+/// to the function's channel. It's parsed with `.parse()`, as the body of a function, and can
+/// use these placeholders:
+///
+/// | Placeholder | Replaced with                                                               |
+/// |-------------|-----------------------------------------------------------------------------|
+/// | `$body`     | A function running the original body                                        |
+/// | `$channel`  | The full name of the function's channel, as in `"orchestrion:undici:fetch"` |
+/// | `$context`  | The context that would be published, as in `{ arguments, self: this }`      |
+/// | `$self`     | The `this` of the traced function                                           |
+/// | `$kind`     | The kind of the function query, `"sync"` or `"async"`                       |
+///
+/// For example, `const span = tracer.startSpan($channel); try { return $body(); } finally {
+/// span.end(); }`. Templates have to call `$body` exactly once, and not within a loop or a nested
+/// function, where it could be called any number of times. They return whatever the traced
+/// function returns, and variables they declare share a scope with the function's parameters, so
+/// they mustn't have the same names.
+///
+/// Templates can't trace constructors, and can't be combined with [`Codegen::Hoisted`], rebound
+/// parameters, around advice or site metadata, which [`InstrumentationConfig::validate`] checks
+/// for.
+///
+/// [`Codegen::Hoisted`]: crate::Codegen::Hoisted
+/// [`InstrumentationConfig::validate`]: crate::InstrumentationConfig::validate
+#[derive(Debug, Clone)]
+pub struct CodeTemplate {
+    source: String,
+    stmts: Vec<Stmt>,
+    uses_context: bool,
+}
+
+/// What the placeholders of a [`CodeTemplate`] are replaced with for a traced function.
+pub(crate) struct Placeholders {
+    pub(crate) body: Expr,
+    pub(crate) channel: Expr,
+    pub(crate) context: Expr,
+    pub(crate) kind: Expr,
+}
+
+impl CodeTemplate {
+    /// Whether the template uses `$context`, which otherwise doesn't need to be created.
+    pub(crate) fn uses_context(&self) -> bool {
+        self.uses_context
+    }
+
+    /// The statements of the template, with its placeholders filled in.
+    pub(crate) fn instantiate(&self, placeholders: &Placeholders) -> Vec<Stmt> {
+        let mut stmts = self.stmts.clone();
+        stmts.visit_mut_with(&mut Instantiate(placeholders));
+        stmts
+    }
+}
+
+impl Display for CodeTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl FromStr for CodeTemplate {
+    type Err = OrchestrionError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| -> OrchestrionError {
+            format!("Invalid code template {input:?}: {reason}").into()
+        };
+        // Parsed as the body of a function, so it can `return`. The closing brace is on its own
+        // line, so it can't end up within a trailing comment.
+        let mut stmts = parse_snippet(&format!("function __apm$template() {{\n{input}\n}}"))
+            .map_err(|e| invalid(&e))?;
+        let body = match stmts.pop() {
+            Some(Stmt::Decl(decl)) if stmts.is_empty() => {
+                decl.fn_decl().and_then(|decl| decl.function.body)
+            }
+            _ => None,
+        };
+        let Some(body) = body else {
+            return Err(invalid("must be the statements of a function body"));
+        };
+
+        let mut uses = PlaceholderUses::default();
+        body.visit_with(&mut uses);
+        if let Some(unknown) = uses.unknown {
+            return Err(invalid(&format!(
+                "unknown placeholder {unknown}, expected one of {}",
+                PLACEHOLDERS.join(", ")
+            )));
+        }
+        if uses.body != 1 || uses.body_calls != 1 {
+            return Err(invalid("must call $body exactly once"));
+        }
+        if uses.body_deferred {
+            return Err(invalid(
+                "must not call $body within a loop or a nested function",
+            ));
+        }
+        Ok(CodeTemplate {
+            source: input.to_string(),
+            stmts: body.stmts,
+            uses_context: uses.context > 0,
+        })
+    }
+}
+
+/// Whether an identifier looks like a placeholder, which rules out `$` on its own, as used by
+/// some libraries.
+fn is_placeholder(sym: &str) -> bool {
+    sym.strip_prefix('$')
+        .is_some_and(|name| name.starts_with(|c: char| c.is_ascii_alphabetic()))
+}
+
+#[derive(Default)]
+struct PlaceholderUses {
+    body: usize,
+    body_calls: usize,
+    /// Whether `$body` is used within a loop or a nested function, which may run it more than
+    /// once, or not at all.
+    body_deferred: bool,
+    context: usize,
+    unknown: Option<String>,
+    deferred_depth: usize,
+}
+
+impl PlaceholderUses {
+    fn visit_deferred<N: VisitWith<Self>>(&mut self, node: &N) {
+        self.deferred_depth += 1;
+        node.visit_children_with(self);
+        self.deferred_depth -= 1;
+    }
+}
+
+impl Visit for PlaceholderUses {
+    // Only identifiers that are expressions are placeholders, rather than names of properties,
+    // for example, which are `IdentName`s.
+    fn visit_ident(&mut self, node: &Ident) {
+        match &*node.sym {
+            "$body" => {
+                self.body += 1;
+                self.body_deferred |= self.deferred_depth > 0;
+            }
+            "$context" => self.context += 1,
+            sym if is_placeholder(sym) && !PLACEHOLDERS.contains(&sym) => {
+                self.unknown.get_or_insert_with(|| sym.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_call_expr(&mut self, node: &CallExpr) {
+        let callee = node.callee.as_expr().and_then(|callee| callee.as_ident());
+        if callee.is_some_and(|callee| callee.sym == "$body") {
+            self.body_calls += 1;
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_for_stmt(&mut self, node: &ForStmt) {
+        self.visit_deferred(node);
+    }
+
+    fn visit_for_in_stmt(&mut self, node: &ForInStmt) {
+        self.visit_deferred(node);
+    }
+
+    fn visit_for_of_stmt(&mut self, node: &ForOfStmt) {
+        self.visit_deferred(node);
+    }
+
+    fn visit_while_stmt(&mut self, node: &WhileStmt) {
+        self.visit_deferred(node);
+    }
+
+    fn visit_do_while_stmt(&mut self, node: &DoWhileStmt) {
+        self.visit_deferred(node);
+    }
+
+    fn visit_function(&mut self, node: &Function) {
+        self.visit_deferred(node);
+    }
+
+    fn visit_arrow_expr(&mut self, node: &ArrowExpr) {
+        self.visit_deferred(node);
+    }
+
+    fn visit_class(&mut self, node: &Class) {
+        self.visit_deferred(node);
+    }
+
+    fn visit_getter_prop(&mut self, node: &GetterProp) {
+        self.visit_deferred(node);
+    }
+
+    fn visit_setter_prop(&mut self, node: &SetterProp) {
+        self.visit_deferred(node);
+    }
+}
+
+struct Instantiate<'a>(&'a Placeholders);
+
+impl Instantiate<'_> {
+    fn placeholder(&self, sym: &str) -> Option<Expr> {
+        match sym {
+            "$body" => Some(self.0.body.clone()),
+            "$channel" => Some(self.0.channel.clone()),
+            "$context" => Some(self.0.context.clone()),
+            "$self" => Some(Expr::This(ThisExpr {
+                span: Span::default(),
+            })),
+            "$kind" => Some(self.0.kind.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl VisitMut for Instantiate<'_> {
+    fn visit_mut_expr(&mut self, node: &mut Expr) {
+        if let Some(value) = node
+            .as_ident()
+            .and_then(|ident| self.placeholder(&ident.sym))
+        {
+            *node = value;
+            return;
+        }
+        node.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_prop(&mut self, node: &mut Prop) {
+        // `{ $context }` is short for `{ $context: $context }`.
+        if let Some((ident, value)) = node
+            .as_shorthand()
+            .and_then(|ident| Some((ident, self.placeholder(&ident.sym)?)))
+        {
+            *node = Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(IdentName::new(ident.sym.clone(), Span::default())),
+                value: Box::new(value),
+            });
+            return;
+        }
+        node.visit_mut_children_with(self);
+    }
+}
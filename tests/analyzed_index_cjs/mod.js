@@ -0,0 +1,53 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+class First {
+  query () {
+    return 'first';
+  }
+}
+
+class Second {
+  query () {
+    return 'second';
+  }
+}
+
+class Third {
+  query () {
+    return 'third';
+  }
+}
+
+function inc (n) {
+  return n + 1;
+}
+
+function next (n) {
+  return n + 1;
+}
+
+function succ (n) {
+  return n + 1;
+}
+
+exports.db = {
+  connect () {
+    return 'first';
+  }
+};
+exports.firstDb = exports.db;
+exports.db = {
+  connect () {
+    return 'second';
+  }
+};
+exports.secondDb = exports.db;
+exports.db = {
+  connect () {
+    return 'third';
+  }
+};
+
+Object.assign(exports, { First, Second, Third, inc, next, succ });
@@ -0,0 +1,49 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn analyzed_index_cjs() {
+    let program = parse_program(file!(), "mod.js");
+    let fingerprints = function_fingerprints(&program);
+    let inc = fingerprints
+        .iter()
+        .find(|function| function.name.as_deref() == Some("inc"))
+        .expect("Couldn't find inc");
+
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "query",
+                    test_module_matcher(),
+                    FunctionQuery::Selector {
+                        selector: "Class > Method[name=query]".parse().unwrap(),
+                        kind: FunctionKind::Sync,
+                        index: 1,
+                    },
+                ),
+                InstrumentationConfig::new(
+                    "inc",
+                    test_module_matcher(),
+                    FunctionQuery::Fingerprint {
+                        fingerprint: inc.fingerprint.clone(),
+                        kind: FunctionKind::Sync,
+                        index: 1,
+                    },
+                ),
+                InstrumentationConfig::new(
+                    "connect",
+                    test_module_matcher(),
+                    FunctionQuery::Exported {
+                        export_path: "db.connect".to_string(),
+                        kind: FunctionKind::Sync,
+                        index: 1,
+                    },
+                ),
+            ],
+            None,
+        ),
+    );
+}
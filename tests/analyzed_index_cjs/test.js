@@ -0,0 +1,28 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { First, Second, Third, inc, next, succ, firstDb, secondDb, db } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+
+// Only the second of the functions each query matches is traced, not those after it.
+const queryContext = getContext('orchestrion:undici:query');
+assert.strictEqual(new First().query(), 'first');
+assert.strictEqual(new Third().query(), 'third');
+assert.deepStrictEqual(queryContext, {});
+assert.strictEqual(new Second().query(), 'second');
+assert.deepStrictEqual(queryContext, { start: true, end: true });
+
+const incContext = getContext('orchestrion:undici:inc');
+assert.strictEqual(inc(1), 2);
+assert.strictEqual(succ(1), 2);
+assert.deepStrictEqual(incContext, {});
+assert.strictEqual(next(1), 2);
+assert.deepStrictEqual(incContext, { start: true, end: true });
+
+const connectContext = getContext('orchestrion:undici:connect');
+assert.strictEqual(firstDb.connect(), 'first');
+assert.strictEqual(db.connect(), 'third');
+assert.deepStrictEqual(connectContext, {});
+assert.strictEqual(secondDb.connect(), 'second');
+assert.deepStrictEqual(connectContext, { start: true, end: true });
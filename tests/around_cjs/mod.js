@@ -0,0 +1,55 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+let calls = 0;
+
+function lookup (key) {
+  calls++;
+  if (key === 'missing') {
+    throw new Error('missing');
+  }
+  return `value of ${key}`;
+}
+
+async function load (key) {
+  calls++;
+  await null;
+  return `loaded ${key}`;
+}
+
+function stream (name) {
+  calls++;
+  return { name };
+}
+
+class Client {
+  constructor (host) {
+    calls++;
+    this.host = host;
+  }
+}
+
+class Base {
+  constructor (size) {
+    this.size = size;
+  }
+}
+
+class Pool extends Base {
+  constructor (size) {
+    calls++;
+    super(size);
+  }
+}
+
+function Connection (host) {
+  calls++;
+  this.host = host;
+}
+
+function getCalls () {
+  return calls;
+}
+
+module.exports = { lookup, load, stream, Client, Pool, Connection, getCalls };
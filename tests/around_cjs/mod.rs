@@ -0,0 +1,22 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn around_cjs() {
+    let around = InstrumentationConfig::with_around_advice;
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                configured("lookup", "lookup():sync", around),
+                configured("load", "load():async", around),
+                configured("stream", "stream():sync", around).with_codegen(Codegen::Hoisted),
+                configured("Client", "new Client", around),
+                configured("Pool", "new Pool", around),
+                configured("Connection", "new Connection()", around),
+            ],
+            None,
+        ),
+    );
+}
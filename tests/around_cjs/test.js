@@ -0,0 +1,107 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { lookup, load, stream, Client, Pool, Connection, getCalls } = require('./instrumented.js');
+const { assert } = require('../common/preamble.js');
+const { tracingChannel } = require('diagnostics_channel');
+
+function advise (channelName, { start = () => {}, end = () => {}, asyncEnd = () => {} }) {
+  tracingChannel(channelName).subscribe({
+    start,
+    end,
+    asyncStart () {},
+    asyncEnd,
+    error () {}
+  });
+}
+
+const cache = { cached: 'cached value' };
+
+(async () => {
+  // Nothing is subscribed yet, so the original functions run as they are.
+  assert.strictEqual(lookup('a'), 'value of a');
+  assert.strictEqual(getCalls(), 1);
+
+  advise('orchestrion:undici:lookup', {
+    start (message) {
+      if (message.arguments[0] in cache) {
+        message.skip = true;
+        message.result = cache[message.arguments[0]];
+      }
+    },
+    end (message) {
+      if (typeof message.result === 'string') {
+        message.result = message.result.toUpperCase();
+      }
+    }
+  });
+  assert.strictEqual(lookup('cached'), 'CACHED VALUE');
+  assert.strictEqual(getCalls(), 1);
+  assert.strictEqual(lookup('b'), 'VALUE OF B');
+  assert.strictEqual(getCalls(), 2);
+  // Errors are thrown on.
+  assert.throws(() => lookup('missing'), /missing/);
+  assert.strictEqual(getCalls(), 3);
+
+  advise('orchestrion:undici:load', {
+    start (message) {
+      if (message.arguments[0] === 'cached') {
+        message.skip = true;
+        message.result = 'cached load';
+      }
+    },
+    asyncEnd (message) {
+      message.result = `${message.result}!`;
+    }
+  });
+  assert.strictEqual(await load('cached'), 'cached load!');
+  assert.strictEqual(getCalls(), 3);
+  assert.strictEqual(await load('a'), 'loaded a!');
+  assert.strictEqual(getCalls(), 4);
+
+  advise('orchestrion:undici:stream', {
+    end (message) {
+      message.result = { wrapped: message.result };
+    }
+  });
+  assert.deepStrictEqual(stream('s'), { wrapped: { name: 's' } });
+  assert.strictEqual(getCalls(), 5);
+
+  const pooled = new Client('pooled');
+  advise('orchestrion:undici:Client', {
+    start (message) {
+      if (message.arguments[0] === 'pooled') {
+        message.skip = true;
+        message.result = pooled;
+      }
+    }
+  });
+  assert.strictEqual(new Client('pooled'), pooled);
+  assert.strictEqual(getCalls(), 6);
+  assert.strictEqual(new Client('other').host, 'other');
+  assert.strictEqual(getCalls(), 7);
+
+  // A derived class's constructor can be skipped, as long as it's given an object.
+  advise('orchestrion:undici:Pool', {
+    start (message) {
+      if (message.arguments[0] === 0) {
+        message.skip = true;
+        message.result = { empty: true };
+      }
+    }
+  });
+  assert.deepStrictEqual(new Pool(0), { empty: true });
+  assert.strictEqual(getCalls(), 7);
+  const pool = new Pool(2);
+  assert.ok(pool instanceof Pool);
+  assert.strictEqual(pool.size, 2);
+
+  advise('orchestrion:undici:Connection', {
+    end (message) {
+      message.result = { proxied: message.self };
+    }
+  });
+  const connection = new Connection('localhost');
+  assert.strictEqual(connection.proxied.host, 'localhost');
+})();
@@ -0,0 +1,53 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+"use strict";
+
+function _classCallCheck(a, n) { if (!(a instanceof n)) throw new TypeError("Cannot call a class as a function"); }
+function _defineProperties(e, r) { for (var t = 0; t < r.length; t++) { var o = r[t]; o.enumerable = o.enumerable || !1, o.configurable = !0, "value" in o && (o.writable = !0), Object.defineProperty(e, o.key, o); } }
+function _createClass(e, r, t) { return r && _defineProperties(e.prototype, r), t && _defineProperties(e, t), Object.defineProperty(e, "prototype", { writable: !1 }), e; }
+
+// Compiled by Babel in spec mode.
+var Undici = /*#__PURE__*/function () {
+  function Undici(url) {
+    _classCallCheck(this, Undici);
+    this.url = url;
+  }
+  return _createClass(Undici, [{
+    key: "fetch",
+    value: function fetch(path) {
+      return this.url + path;
+    }
+  }, {
+    key: "close",
+    value: function close() {
+      return 'closed';
+    }
+  }], [{
+    key: "create",
+    value: function create(url) {
+      return new Undici(url);
+    }
+  }]);
+}();
+
+// Compiled by Babel in loose mode.
+var Pool = /*#__PURE__*/function () {
+  function Pool(size) {
+    this.size = size;
+  }
+  var _proto = Pool.prototype;
+  _proto.fetch = function fetch(path) {
+    return 'pool ' + this.size + ' ' + path;
+  };
+  _proto.close = function close() {
+    return 'pool closed';
+  };
+  Pool.create = function create(size) {
+    return new Pool(size);
+  };
+  return Pool;
+}();
+
+module.exports = { Undici, Pool };
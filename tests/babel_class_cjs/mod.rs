@@ -0,0 +1,25 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn babel_class_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "Undici_constructor",
+                    test_module_matcher(),
+                    FunctionQuery::class_constructor("Undici"),
+                ),
+                InstrumentationConfig::new(
+                    "{class}_{name}",
+                    test_module_matcher(),
+                    FunctionQuery::class_method("*", "/^(fetch|create)$/", FunctionKind::Sync),
+                ),
+            ],
+            None,
+        ),
+    );
+}
@@ -0,0 +1,32 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Undici, Pool } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const constructorContext = getContext('orchestrion:undici:Undici_constructor');
+const fetchContext = getContext('orchestrion:undici:Undici_fetch');
+const createContext = getContext('orchestrion:undici:Undici_create');
+const closeContext = getContext('orchestrion:undici:Undici_close');
+const poolFetchContext = getContext('orchestrion:undici:Pool_fetch');
+const poolCloseContext = getContext('orchestrion:undici:Pool_close');
+const poolCreateContext = getContext('orchestrion:undici:Pool_create');
+
+const undici = Undici.create('https://example.com');
+assert.ok(undici instanceof Undici);
+assert.deepStrictEqual(createContext, { start: true, end: true });
+assert.deepStrictEqual(constructorContext, { start: true, end: true });
+assert.throws(() => Undici('https://example.com'), TypeError);
+
+assert.strictEqual(undici.fetch('/users'), 'https://example.com/users');
+assert.deepStrictEqual(fetchContext, { start: true, end: true });
+assert.strictEqual(undici.close(), 'closed');
+assert.deepStrictEqual(closeContext, {});
+
+const pool = Pool.create(2);
+assert.ok(pool instanceof Pool);
+assert.deepStrictEqual(poolCreateContext, { start: true, end: true });
+assert.strictEqual(pool.fetch('/users'), 'pool 2 /users');
+assert.deepStrictEqual(poolFetchContext, { start: true, end: true });
+assert.strictEqual(pool.close(), 'pool closed');
+assert.deepStrictEqual(poolCloseContext, {});
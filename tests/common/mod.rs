@@ -12,9 +12,22 @@ use swc::{
     config::{IsModule, SourceMapsConfig},
     try_with_handler, Compiler, HandlerOpts, PrintArgs,
 };
-use swc_core::common::{comments::Comments, errors::ColorConfig, FileName, FilePathMapping};
-use swc_core::ecma::ast::EsVersion;
-use swc_ecma_parser::{EsSyntax, Syntax};
+use swc_core::common::{
+    comments::{Comments, SingleThreadedComments},
+    errors::ColorConfig,
+    FileName, FilePathMapping, Globals, Mark, GLOBALS,
+};
+use swc_core::ecma::ast::{EsVersion, Pass, Program};
+use swc_core::ecma::transforms::{
+    base::{
+        helpers::{inject_helpers, Helpers, HELPERS},
+        resolver,
+    },
+    proposal::decorators::{decorators, Config as DecoratorsConfig},
+    react::{jsx, Options as JsxOptions, Runtime},
+    typescript::strip,
+};
+use swc_ecma_parser::{parse_file_as_program, EsSyntax, Syntax};
 use swc_ecma_visit::VisitMutWith;
 
 fn print_result(original: &str, modified: &str) {
@@ -24,14 +37,60 @@ fn print_result(original: &str, modified: &str) {
     );
 }
 
+/// The kinds of files tests can instrument.
+#[derive(Clone, Copy)]
+enum Source {
+    Script,
+    Module,
+    /// A TypeScript script, whose types are stripped once it has been instrumented, so that it
+    /// can be run.
+    TypeScript,
+}
+
+impl Source {
+    fn extension(self) -> &'static str {
+        match self {
+            Source::Script => "js",
+            Source::Module => "mjs",
+            Source::TypeScript => "ts",
+        }
+    }
+
+    /// The extension of the instrumented file, which is always JavaScript.
+    fn output_extension(self) -> &'static str {
+        match self {
+            Source::Module => "mjs",
+            Source::Script | Source::TypeScript => "js",
+        }
+    }
+}
+
+fn print(compiler: &Compiler, program: &Program) -> String {
+    compiler
+        .print(
+            program,
+            PrintArgs {
+                source_file_name: None,
+                source_map: SourceMapsConfig::Bool(false),
+                comments: None,
+                emit_source_map_columns: false,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .code
+}
+
 fn transpile(
     contents: &str,
-    is_module: IsModule,
+    is_module: bool,
+    syntax: SourceSyntax,
     instrumentation: &mut InstrumentationVisitor,
 ) -> String {
     let compiler = Compiler::new(Arc::new(swc_core::common::SourceMap::new(
         FilePathMapping::empty(),
     )));
+    instrumentation.set_source_map(compiler.cm.clone());
     try_with_handler(
         compiler.cm.clone(),
         HandlerOpts {
@@ -44,65 +103,155 @@ fn transpile(
                 contents.to_string(),
             );
 
-            let program = compiler
+            let mut program = compiler
                 .parse_js(
                     source_file.to_owned(),
                     handler,
                     EsVersion::latest(),
-                    Syntax::Es(EsSyntax {
-                        explicit_resource_management: true,
-                        import_attributes: true,
-                        decorators: true,
-                        ..Default::default()
-                    }),
-                    is_module,
+                    syntax.syntax(),
+                    IsModule::Bool(is_module),
                     Some(&compiler.comments() as &dyn Comments),
                 )
-                .map(|mut program| {
-                    program.visit_mut_with(instrumentation);
-                    program
-                })
-                .unwrap();
-            let result = compiler
-                .print(
-                    &program,
-                    PrintArgs {
-                        source_file_name: None,
-                        source_map: SourceMapsConfig::Bool(false),
-                        comments: None,
-                        emit_source_map_columns: false,
-                        ..Default::default()
-                    },
-                )
                 .unwrap();
+            program.visit_mut_with(instrumentation);
+            let typescript = syntax != SourceSyntax::JavaScript;
+            if typescript {
+                assert_valid(&compiler, &program, syntax);
+            }
+            lower(&compiler, &mut program, typescript);
+            let result = print(&compiler, &program);
 
-            print_result(contents, &result.code);
-            Ok(result.code)
+            print_result(contents, &result);
+            Ok(result)
         },
     )
     .unwrap()
 }
 
-static TEST_MODULE_NAME: &'static str = "undici";
-static TEST_MODULE_PATH: &'static str = "index.mjs";
+/// Instrumented TypeScript has to still be valid TypeScript, since it's usually compiled or
+/// type-checked by something else afterwards.
+fn assert_valid(compiler: &Compiler, program: &Program, syntax: SourceSyntax) {
+    let code = print(compiler, program);
+    let source_file = compiler
+        .cm
+        .new_source_file(Arc::new(FileName::Anon), code.clone());
+    let mut errors = vec![];
+    let parsed = parse_file_as_program(
+        &source_file,
+        syntax.syntax(),
+        EsVersion::latest(),
+        None,
+        &mut errors,
+    );
+    assert!(
+        parsed.is_ok() && errors.is_empty(),
+        "Instrumented code doesn't parse: {parsed:?} {errors:?}\n{code}"
+    );
+}
+
+/// Compiles what Node.js can't run yet once a file has been instrumented: decorators, the way
+/// TypeScript compiles them with `experimentalDecorators`, types, and JSX, to
+/// `React.createElement` calls, which tests provide themselves. This does nothing to plain
+/// JavaScript files.
+fn lower(compiler: &Compiler, program: &mut Program, typescript: bool) {
+    GLOBALS.set(&Globals::new(), || {
+        let unresolved_mark = Mark::new();
+        let top_level_mark = Mark::new();
+        resolver(unresolved_mark, top_level_mark, typescript).process(program);
+        HELPERS.set(&Helpers::new(false), || {
+            decorators(DecoratorsConfig {
+                legacy: true,
+                ..Default::default()
+            })
+            .process(program);
+            if typescript {
+                strip(unresolved_mark, top_level_mark).process(program);
+            }
+            jsx(
+                compiler.cm.clone(),
+                None::<SingleThreadedComments>,
+                JsxOptions {
+                    runtime: Some(Runtime::Classic),
+                    ..Default::default()
+                },
+                top_level_mark,
+                unresolved_mark,
+            )
+            .process(program);
+            inject_helpers(Mark::new()).process(program);
+        });
+    });
+}
+
+/// Parses a file in the test directory without instrumenting it, such as a reference build to
+/// take fingerprints from.
+pub fn parse_program(test_file: &str, file_name: &str) -> Program {
+    let test_dir = PathBuf::from(test_file);
+    let test_dir = test_dir.parent().expect("Couldn't find test directory");
+    let contents = std::fs::read_to_string(test_dir.join(file_name)).unwrap();
+    let cm = swc_core::common::SourceMap::new(FilePathMapping::empty());
+    let source_file =
+        cm.new_source_file(Arc::new(FileName::Real(PathBuf::from(file_name))), contents);
+    parse_file_as_program(
+        &source_file,
+        Syntax::Es(EsSyntax::default()),
+        EsVersion::latest(),
+        None,
+        &mut vec![],
+    )
+    .unwrap()
+}
+
+static TEST_MODULE_NAME: &str = "undici";
+static TEST_MODULE_PATH: &str = "index.mjs";
 
 pub fn transpile_and_test(test_file: &str, mjs: bool, config: Config) {
+    transpile_and_test_with(test_file, mjs, config, |_| {});
+}
+
+/// Like [`transpile_and_test`], but also passes the visitor to `check` once the file has been
+/// instrumented, before the test file is run.
+pub fn transpile_and_test_with<F>(test_file: &str, mjs: bool, config: Config, check: F)
+where
+    F: FnOnce(&InstrumentationVisitor),
+{
+    let source = if mjs { Source::Module } else { Source::Script };
+    run_test(test_file, source, config, check);
+}
+
+/// Like [`transpile_and_test`], but instruments `mod.ts`, which is run as a script once its
+/// types have been stripped.
+pub fn transpile_and_test_ts(test_file: &str, config: Config) {
+    run_test(test_file, Source::TypeScript, config, |_| {});
+}
+
+fn run_test<F>(test_file: &str, source: Source, config: Config, check: F)
+where
+    F: FnOnce(&InstrumentationVisitor),
+{
     let test_file = PathBuf::from(test_file);
     let test_dir = test_file.parent().expect("Couldn't find test directory");
 
     let file_path = PathBuf::from("index.mjs");
-    let mut instrumentor = Instrumentor::new(config);
-    let mut instrumentations =
-        instrumentor.get_matching_instrumentations(TEST_MODULE_NAME, "0.0.1", &file_path);
+    let mut instrumentor = Instrumentor::new(config).unwrap();
 
-    let extension = if mjs { "mjs" } else { "js" };
-    let instrumentable = test_dir.join(format!("mod.{}", extension));
+    let instrumentable = test_dir.join(format!("mod.{}", source.extension()));
     let mut file = std::fs::File::open(&instrumentable).unwrap();
     let mut contents = String::new();
     file.read_to_string(&mut contents).unwrap();
+    let syntax = instrumentor.source_syntax(&instrumentable, &contents);
 
-    let result = transpile(&contents, IsModule::Bool(mjs), &mut instrumentations);
+    let mut instrumentations =
+        instrumentor.get_matching_instrumentations(TEST_MODULE_NAME, "0.0.1", &file_path);
+    let result = transpile(
+        &contents,
+        matches!(source, Source::Module),
+        syntax,
+        &mut instrumentations,
+    );
+    check(&instrumentations);
 
+    let extension = source.output_extension();
     let instrumented_file = test_dir.join(format!("instrumented.{}", extension));
     let mut file = std::fs::File::create(&instrumented_file).unwrap();
     file.write_all(result.as_bytes()).unwrap();
@@ -120,3 +269,15 @@ pub fn transpile_and_test(test_file: &str, mjs: bool, config: Config) {
 pub fn test_module_matcher() -> ModuleMatcher {
     ModuleMatcher::new(TEST_MODULE_NAME, ">=0.0.1", TEST_MODULE_PATH).unwrap()
 }
+
+/// An instrumentation of the test module for a query string, with the options `configure` sets.
+pub fn configured<F>(channel_name: &str, query: &str, configure: F) -> InstrumentationConfig
+where
+    F: FnOnce(InstrumentationConfig) -> InstrumentationConfig,
+{
+    configure(InstrumentationConfig::new(
+        channel_name,
+        test_module_matcher(),
+        query.parse().unwrap(),
+    ))
+}
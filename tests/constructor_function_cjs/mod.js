@@ -0,0 +1,26 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+var EventEmitter = require('events').EventEmitter;
+var util = require('util');
+
+function Connection (options) {
+  if (!(this instanceof Connection)) {
+    return new Connection(options);
+  }
+  EventEmitter.call(this);
+  if (!options.host) {
+    throw new Error('missing host');
+  }
+  this.host = options.host;
+}
+util.inherits(Connection, EventEmitter);
+
+var PoolConnection = function (options) {
+  Connection.call(this, options);
+  this.pooled = true;
+};
+util.inherits(PoolConnection, Connection);
+
+module.exports = { Connection: Connection, PoolConnection: PoolConnection };
@@ -0,0 +1,25 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn constructor_function_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "{name}_constructor",
+                    test_module_matcher(),
+                    FunctionQuery::function_constructor("Connection"),
+                ),
+                InstrumentationConfig::new(
+                    "{class}_constructor",
+                    test_module_matcher(),
+                    "new PoolConnection()".parse().unwrap(),
+                ),
+            ],
+            None,
+        ),
+    );
+}
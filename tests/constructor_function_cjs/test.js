@@ -0,0 +1,44 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Connection, PoolConnection } = require('./instrumented.js');
+const { assert } = require('../common/preamble.js');
+const { tracingChannel } = require('diagnostics_channel');
+
+function record (channelName) {
+  const events = [];
+  tracingChannel(channelName).subscribe({
+    start (ctx) { events.push(['start', ctx.self]); },
+    end (ctx) { events.push(['end', ctx.self]); },
+    error (ctx) { events.push(['error', ctx.error.message]); },
+    asyncStart () {},
+    asyncEnd () {}
+  });
+  return events;
+}
+
+const connectionEvents = record('orchestrion:undici:Connection_constructor');
+const poolEvents = record('orchestrion:undici:PoolConnection_constructor');
+
+const conn = new Connection({ host: 'localhost' });
+assert.strictEqual(conn.host, 'localhost');
+assert.deepStrictEqual(connectionEvents, [['start', undefined], ['end', conn]]);
+
+// Calls without `new` are only traced once they've called `new` themselves.
+connectionEvents.length = 0;
+const called = Connection({ host: 'example.com' });
+assert.ok(called instanceof Connection);
+assert.deepStrictEqual(connectionEvents, [['start', undefined], ['end', called]]);
+
+connectionEvents.length = 0;
+assert.throws(() => new Connection({}), /missing host/);
+assert.deepStrictEqual(connectionEvents.map(([event]) => event), ['start', 'error', 'end']);
+assert.strictEqual(connectionEvents[1][1], 'missing host');
+
+// Subclasses calling the constructor as a plain function aren't traced as constructing it.
+connectionEvents.length = 0;
+const pooled = new PoolConnection({ host: 'localhost' });
+assert.ok(pooled.pooled);
+assert.deepStrictEqual(connectionEvents, []);
+assert.deepStrictEqual(poolEvents, [['start', undefined], ['end', pooled]]);
@@ -0,0 +1,27 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function fetch (url, options) {
+  return String(url);
+}
+
+class Client {
+  constructor (host) {
+    this.host = host;
+  }
+
+  async request (path) {
+    return this.host + path;
+  }
+}
+
+function send (message) {
+  return message.body;
+}
+
+function receive (message) {
+  return message.body;
+}
+
+module.exports = { fetch, Client, send, receive };
@@ -0,0 +1,45 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn context_builder_cjs() {
+    assert!("{ url: ".parse::<ContextBuilder>().is_err());
+    assert!("a), (b".parse::<ContextBuilder>().is_err());
+    assert!("a); steal(); (b".parse::<ContextBuilder>().is_err());
+    assert!("".parse::<ContextBuilder>().is_err());
+    assert_eq!(
+        "{ url }".parse::<ContextBuilder>().unwrap().to_string(),
+        "{ url }"
+    );
+
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                configured("fetch", "fetch():sync", |config| {
+                    config.with_context_builder(
+                        "{ url: arguments[0]?.href ?? arguments[0], method: options?.method }"
+                            .parse()
+                            .unwrap(),
+                    )
+                }),
+                configured("request", "Client#request:async", |config| {
+                    config.with_context_builder(
+                        "{ host: this.host, path } // the path within the host"
+                            .parse()
+                            .unwrap(),
+                    )
+                })
+                .with_codegen(Codegen::Hoisted),
+                configured("send", "send():sync", |config| {
+                    config.with_context_builder("message.headers".parse().unwrap())
+                }),
+                configured("receive", "receive():sync", |config| {
+                    config.with_context_builder("{ id: message.headers.id }".parse().unwrap())
+                }),
+            ],
+            None,
+        ),
+    );
+}
@@ -0,0 +1,52 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { fetch, Client, send, receive } = require('./instrumented.js');
+const { assert } = require('../common/preamble.js');
+const { tracingChannel } = require('diagnostics_channel');
+
+function record (channelName) {
+  const contexts = [];
+  tracingChannel(channelName).subscribe({
+    start (message) {
+      contexts.push(message);
+    },
+    end () {},
+    asyncStart () {},
+    asyncEnd () {},
+    error () {}
+  });
+  return contexts;
+}
+
+(async () => {
+  const fetchContexts = record('orchestrion:undici:fetch');
+  assert.strictEqual(fetch(new URL('https://example.com/a'), { method: 'POST' }), 'https://example.com/a');
+  assert.strictEqual(fetch('/b'), '/b');
+  assert.strictEqual(fetchContexts[0].url, 'https://example.com/a');
+  assert.strictEqual(fetchContexts[0].method, 'POST');
+  assert.strictEqual(fetchContexts[1].url, '/b');
+  assert.strictEqual(fetchContexts[1].method, undefined);
+  // What's published otherwise is still there.
+  assert.strictEqual(fetchContexts[1].arguments[0], '/b');
+
+  const requestContexts = record('orchestrion:undici:request');
+  const client = new Client('https://example.com');
+  assert.strictEqual(await client.request('/users'), 'https://example.com/users');
+  assert.strictEqual(requestContexts[0].host, 'https://example.com');
+  assert.strictEqual(requestContexts[0].path, '/users');
+  assert.strictEqual(requestContexts[0].self, client);
+
+  const sendContexts = record('orchestrion:undici:send');
+  assert.strictEqual(send({ headers: { 'x-id': '1' }, body: 'hello' }), 'hello');
+  assert.strictEqual(sendContexts[0]['x-id'], '1');
+
+  // A builder that throws adds nothing, and the function still runs.
+  const receiveContexts = record('orchestrion:undici:receive');
+  assert.strictEqual(receive({ headers: { id: '1' }, body: 'hello' }), 'hello');
+  assert.strictEqual(receive({ body: 'bye' }), 'bye');
+  assert.strictEqual(receiveContexts[0].id, '1');
+  assert.strictEqual(receiveContexts.length, 2);
+  assert.ok(!('id' in receiveContexts[1]));
+})();
@@ -0,0 +1,49 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const routes = [];
+
+// Records metadata about the method, like `Reflect.metadata` does.
+function Get (path) {
+  return (target, key, descriptor) => {
+    routes.push({ path, key, handler: descriptor.value });
+  };
+}
+
+// Replaces the method with a wrapper, like most method decorators do.
+function Uppercase (target, key, descriptor) {
+  const original = descriptor.value;
+  descriptor.value = async function (...args) {
+    return (await original.apply(this, args)).toUpperCase();
+  };
+  return descriptor;
+}
+
+class UsersController {
+  constructor () {
+    this.name = 'users';
+  }
+
+  @Get('/users')
+  async findAll () {
+    return this.name;
+  }
+
+  @Get('/users/')
+  async list () {
+    return [this.name];
+  }
+
+  @Get('/users/:id')
+  @Uppercase
+  async findOne (id) {
+    return `${this.name} ${id}`;
+  }
+
+  async count () {
+    return 2;
+  }
+}
+
+module.exports = { UsersController, routes };
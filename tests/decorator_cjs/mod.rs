@@ -0,0 +1,34 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn decorator_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "route_{name}",
+                    test_module_matcher(),
+                    FunctionQuery::class_method("UsersController", "*", FunctionKind::Async)
+                        .with_decorator(DecoratorQuery::named("Get")),
+                ),
+                InstrumentationConfig::new(
+                    "trailing_slash",
+                    test_module_matcher(),
+                    FunctionQuery::class_method("UsersController", "*", FunctionKind::Async)
+                        .with_decorator(DecoratorQuery::named("Get").with_args(&["/users/"])),
+                ),
+                InstrumentationConfig::new(
+                    "by_id",
+                    test_module_matcher(),
+                    "UsersController#*:async where @Get(/users/:id)"
+                        .parse()
+                        .unwrap(),
+                ),
+            ],
+            None,
+        ),
+    );
+}
@@ -0,0 +1,36 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { UsersController, routes } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const findAllContext = getContext('orchestrion:undici:route_findAll');
+const findOneContext = getContext('orchestrion:undici:route_findOne');
+const byIdContext = getContext('orchestrion:undici:by_id');
+const trailingSlashContext = getContext('orchestrion:undici:trailing_slash');
+
+(async () => {
+  const controller = new UsersController();
+
+  assert.deepStrictEqual(routes.map(({ path, key }) => [path, key]), [
+    ['/users', 'findAll'],
+    ['/users/', 'list'],
+    ['/users/:id', 'findOne'],
+  ]);
+  assert.strictEqual(routes[0].handler, UsersController.prototype.findAll);
+
+  assert.strictEqual(await controller.count(), 2);
+
+  assert.strictEqual(await controller.findAll(), 'users');
+  assert.deepStrictEqual(findAllContext, { start: true, end: true, asyncStart: 'users', asyncEnd: 'users' });
+  assert.deepStrictEqual(findOneContext, {});
+  assert.deepStrictEqual(byIdContext, {});
+
+  assert.strictEqual(await controller.findOne(1), 'USERS 1');
+  assert.deepStrictEqual(findOneContext, { start: true, end: true, asyncStart: 'users 1', asyncEnd: 'users 1' });
+  assert.deepStrictEqual(byIdContext, { start: true, end: true, asyncStart: 'users 1', asyncEnd: 'users 1' });
+  assert.deepStrictEqual(trailingSlashContext, {});
+
+  assert.deepStrictEqual(await controller.list(), ['users']);
+  assert.deepStrictEqual(trailingSlashContext, { start: true, end: true, asyncStart: ['users'], asyncEnd: ['users'] });
+})();
@@ -0,0 +1,36 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const EventEmitter = require('events');
+
+const events = [];
+
+class Base extends EventEmitter {
+  constructor (size) {
+    events.push('super');
+    if (size < 0) {
+      throw new RangeError('negative size');
+    }
+    super();
+  }
+}
+
+class Pool extends Base {
+  constructor (size, options = {}) {
+    const max = options.max || size;
+    if (max > 10) {
+      super(max);
+      this.capped = true;
+    } else {
+      const init = () => super(max);
+      init();
+    }
+    if (options.fail) {
+      throw new Error('failed after super');
+    }
+    this.size = max;
+  }
+}
+
+module.exports = { Pool, events };
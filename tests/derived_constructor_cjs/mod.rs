@@ -0,0 +1,15 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn derived_constructor_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new_single(InstrumentationConfig::new(
+            "Pool_constructor",
+            test_module_matcher(),
+            FunctionQuery::class_constructor("Pool"),
+        )),
+    );
+}
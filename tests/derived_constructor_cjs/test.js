@@ -0,0 +1,42 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Pool, events } = require('./instrumented.js');
+const { assert } = require('../common/preamble.js');
+const { tracingChannel } = require('diagnostics_channel');
+
+tracingChannel('orchestrion:undici:Pool_constructor').subscribe({
+  start (ctx) { events.push(['start', ctx.self]); },
+  end (ctx) { events.push(['end', ctx.self]); },
+  error (ctx) { events.push(['error', ctx.error, ctx.self]); },
+  asyncStart () {},
+  asyncEnd () {}
+});
+
+// Start is published before `super()` runs, and `self` is the object it initialized.
+const capped = new Pool(20);
+assert.strictEqual(capped.size, 20);
+assert.ok(capped.capped);
+assert.deepStrictEqual(events, [['start', undefined], 'super', ['end', capped]]);
+
+// `super()` can be called from an arrow function as well.
+events.length = 0;
+const pool = new Pool(2);
+assert.strictEqual(pool.size, 2);
+assert.deepStrictEqual(events, [['start', undefined], 'super', ['end', pool]]);
+
+// Errors thrown by `super()` itself aren't replaced with a `ReferenceError` for touching `this`.
+events.length = 0;
+assert.throws(() => new Pool(-1), RangeError);
+assert.strictEqual(events.length, 4);
+assert.ok(events[2][1] instanceof RangeError);
+assert.strictEqual(events[2][2], undefined);
+assert.deepStrictEqual(events[3], ['end', undefined]);
+
+// Errors thrown after `super()` has returned come with the object it initialized.
+events.length = 0;
+assert.throws(() => new Pool(2, { fail: true }), /failed after super/);
+assert.strictEqual(events[2][1].message, 'failed after super');
+assert.ok(events[2][2] instanceof Pool);
+assert.strictEqual(events[3][1], events[2][2]);
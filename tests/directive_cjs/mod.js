@@ -0,0 +1,34 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function strictSelf () {
+  'use strict';
+  return this;
+}
+
+function sloppySelf () {
+  return this;
+}
+
+function hoistedSelf () {
+  'use strict';
+  return this;
+}
+
+function withDefaults (a = 1, { b } = { b: 2 }) {
+  'use asm';
+  return a + b;
+}
+
+function Connection (host) {
+  'use strict';
+  try {
+    undeclared = host;
+  } catch (err) {
+    this.strict = err instanceof ReferenceError;
+  }
+  this.host = host;
+}
+
+module.exports = { strictSelf, sloppySelf, hoistedSelf, withDefaults, Connection };
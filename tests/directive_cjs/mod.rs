@@ -0,0 +1,36 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn directive_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "self",
+                    test_module_matcher(),
+                    "/^(strict|sloppy)Self$/():sync".parse().unwrap(),
+                ),
+                InstrumentationConfig::new(
+                    "hoisted",
+                    test_module_matcher(),
+                    "hoistedSelf():sync".parse().unwrap(),
+                )
+                .with_codegen(Codegen::Hoisted),
+                InstrumentationConfig::new(
+                    "defaults",
+                    test_module_matcher(),
+                    "withDefaults():sync".parse().unwrap(),
+                ),
+                InstrumentationConfig::new(
+                    "connection",
+                    test_module_matcher(),
+                    "new Connection()".parse().unwrap(),
+                ),
+            ],
+            None,
+        ),
+    );
+}
@@ -0,0 +1,32 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { strictSelf, sloppySelf, hoistedSelf, withDefaults, Connection } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+
+const selfContext = getContext('orchestrion:undici:self');
+const hoistedContext = getContext('orchestrion:undici:hoisted');
+const defaultsContext = getContext('orchestrion:undici:defaults');
+const connectionContext = getContext('orchestrion:undici:connection');
+
+// `this` isn't boxed in strict mode functions, and is the global object for calls without one.
+assert.strictEqual(strictSelf.call(5), 5);
+assert.strictEqual(strictSelf(), undefined);
+assert.deepStrictEqual(selfContext, { start: true, end: true });
+assert.ok(sloppySelf.call(5) instanceof Number);
+assert.strictEqual(sloppySelf(), globalThis);
+
+assert.strictEqual(hoistedSelf.call(5), 5);
+assert.strictEqual(hoistedSelf(), undefined);
+assert.deepStrictEqual(hoistedContext, { start: true, end: true });
+
+assert.strictEqual(withDefaults(), 3);
+assert.strictEqual(withDefaults(2, { b: 3 }), 5);
+assert.deepStrictEqual(defaultsContext, { start: true, end: true });
+
+const connection = new Connection('localhost');
+assert.strictEqual(connection.strict, true);
+assert.strictEqual(connection.host, 'localhost');
+assert.strictEqual(typeof undeclared, 'undefined');
+assert.deepStrictEqual(connectionContext, { start: true, end: true });
@@ -0,0 +1,44 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn downlevel_async_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "Client_{name}",
+                    test_module_matcher(),
+                    FunctionQuery::class_method(
+                        "Client",
+                        "/^(fetch|close|ping)$/",
+                        FunctionKind::Async,
+                    ),
+                ),
+                InstrumentationConfig::new(
+                    "query",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("query", FunctionKind::Async),
+                ),
+                InstrumentationConfig::new(
+                    "connect",
+                    test_module_matcher(),
+                    FunctionQuery::object_method("connect", FunctionKind::Async),
+                ),
+                InstrumentationConfig::new(
+                    "count",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("count", FunctionKind::Async),
+                ),
+                InstrumentationConfig::new(
+                    "Pool_acquire",
+                    test_module_matcher(),
+                    FunctionQuery::class_method("Pool", "acquire", FunctionKind::Async),
+                ),
+            ],
+            None,
+        ),
+    );
+}
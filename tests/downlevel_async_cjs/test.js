@@ -0,0 +1,40 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Client, query, pool, count, Pool } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const clientContext = getContext('orchestrion:undici:Client_fetch');
+const closeContext = getContext('orchestrion:undici:Client_close');
+const pingContext = getContext('orchestrion:undici:Client_ping');
+const queryContext = getContext('orchestrion:undici:query');
+const connectContext = getContext('orchestrion:undici:connect');
+const countContext = getContext('orchestrion:undici:count');
+const acquireContext = getContext('orchestrion:undici:Pool_acquire');
+
+(async () => {
+  const client = new Client('https://example.com');
+  assert.strictEqual(await client.fetch('/users'), 'https://example.com/users');
+  assert.deepStrictEqual(clientContext, {
+    start: true,
+    end: true,
+    asyncStart: 'https://example.com/users',
+    asyncEnd: 'https://example.com/users',
+  });
+  assert.strictEqual(await client.close(), 'closed');
+  assert.deepStrictEqual(closeContext, { start: true, end: true, asyncStart: 'closed', asyncEnd: 'closed' });
+  assert.strictEqual(client.ping(), 'pong');
+  assert.deepStrictEqual(pingContext, {});
+
+  assert.deepStrictEqual(await query('SELECT 1'), ['SELECT 1']);
+  assert.deepStrictEqual(queryContext.asyncEnd, ['SELECT 1']);
+
+  assert.strictEqual(await pool.connect(1), 'pool 1');
+  assert.deepStrictEqual(connectContext, { start: true, end: true, asyncStart: 'pool 1', asyncEnd: 'pool 1' });
+
+  // Babel's functions delegating to the compiled async function aren't recognized.
+  assert.strictEqual(await count('abc'), 3);
+  assert.deepStrictEqual(countContext, {});
+  assert.strictEqual(await new Pool(2).acquire(), 2);
+  assert.deepStrictEqual(acquireContext, {});
+})();
@@ -0,0 +1,30 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+{
+  class Undici {
+    async fetch (url) {
+      return 1;
+    }
+  }
+
+  exports.Undici1 = Undici;
+}
+{
+  class Undici {
+    async fetch (url) {
+      return 2;
+    }
+  }
+
+  exports.Undici2 = Undici;
+}
+
+function Client () {}
+
+module.exports.Client = Client;
+
+module.exports.Client.prototype.query = async function (sql) {
+  return sql;
+};
@@ -0,0 +1,25 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn export_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "Undici_fetch",
+                    test_module_matcher(),
+                    FunctionQuery::exported("Undici2.fetch", FunctionKind::Async),
+                ),
+                InstrumentationConfig::new(
+                    "Client_query",
+                    test_module_matcher(),
+                    FunctionQuery::exported("Client.prototype.query", FunctionKind::Async),
+                ),
+            ],
+            None,
+        ),
+    );
+}
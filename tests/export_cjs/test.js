@@ -0,0 +1,28 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Undici1, Undici2, Client } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const fetchContext = getContext('orchestrion:undici:Undici_fetch');
+const queryContext = getContext('orchestrion:undici:Client_query');
+
+(async () => {
+  assert.strictEqual(await new Undici1().fetch('https://example.com'), 1);
+  assert.deepStrictEqual(fetchContext, {});
+  assert.strictEqual(await new Undici2().fetch('https://example.com'), 2);
+  assert.deepStrictEqual(fetchContext, {
+    start: true,
+    end: true,
+    asyncStart: 2,
+    asyncEnd: 2
+  });
+  const result = await new Client().query('SELECT 1');
+  assert.strictEqual(result, 'SELECT 1');
+  assert.deepStrictEqual(queryContext, {
+    start: true,
+    end: true,
+    asyncStart: 'SELECT 1',
+    asyncEnd: 'SELECT 1'
+  });
+})();
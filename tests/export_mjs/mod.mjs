@@ -0,0 +1,13 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+async function fetch (url) {
+  return 41;
+}
+
+async function a (url) {
+  return 42;
+}
+
+export { a as fetch, fetch as fetchOld };
@@ -0,0 +1,15 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn export_mjs() {
+    transpile_and_test(
+        file!(),
+        true,
+        Config::new_single(InstrumentationConfig::new(
+            "fetch_export",
+            test_module_matcher(),
+            FunctionQuery::exported("fetch", FunctionKind::Async),
+        )),
+    );
+}
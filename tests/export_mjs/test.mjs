@@ -0,0 +1,19 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+import { fetch, fetchOld } from './instrumented.mjs';
+import { assert, getContext } from '../common/preamble.js';
+const context = getContext('orchestrion:undici:fetch_export');
+
+(async () => {
+  assert.strictEqual(await fetchOld('https://example.com'), 41);
+  assert.deepStrictEqual(context, {});
+  assert.strictEqual(await fetch('https://example.com'), 42);
+  assert.deepStrictEqual(context, {
+    start: true,
+    end: true,
+    asyncStart: 42,
+    asyncEnd: 42
+  });
+})();
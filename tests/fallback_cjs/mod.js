@@ -0,0 +1,19 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+class Client {
+  dispatch (opts) {
+    return 'dispatch';
+  }
+
+  request (opts) {
+    return this.dispatch(opts);
+  }
+}
+
+function _doRequest (opts) {
+  return 'unrelated';
+}
+
+module.exports = { Client, _doRequest };
@@ -0,0 +1,42 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn fallback_cjs() {
+    let query: FunctionQuery =
+        "Client#_doRequest:sync | Client#dispatch:sync | Client#request:sync"
+            .parse()
+            .unwrap();
+    assert_eq!(
+        query.to_string(),
+        "Client#_doRequest:sync | Client#dispatch:sync | Client#request:sync"
+    );
+    assert!(FunctionQuery::first_of(vec![query.clone()])
+        .validate()
+        .is_err());
+    assert!(
+        Instrumentor::new(Config::new_single(InstrumentationConfig::new(
+            "request",
+            test_module_matcher(),
+            FunctionQuery::first_of(vec![query.clone()]),
+        )))
+        .is_err()
+    );
+
+    transpile_and_test_with(
+        file!(),
+        false,
+        Config::new_single(InstrumentationConfig::new(
+            "request",
+            test_module_matcher(),
+            query,
+        )),
+        |visitor| {
+            let applied = visitor.applied_alternatives();
+            assert_eq!(applied.len(), 1);
+            assert_eq!(applied[0].channel_name, "request");
+            assert_eq!(applied[0].index, 1);
+            assert_eq!(applied[0].query.to_string(), "Client#dispatch:sync");
+        },
+    );
+}
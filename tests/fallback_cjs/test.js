@@ -0,0 +1,13 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Client, _doRequest } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const context = getContext('orchestrion:undici:request');
+
+assert.strictEqual(_doRequest({}), 'unrelated');
+assert.deepStrictEqual(context, {});
+
+assert.strictEqual(new Client().dispatch({}), 'dispatch');
+assert.deepStrictEqual(context, { start: true, end: true });
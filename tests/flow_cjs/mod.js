@@ -0,0 +1,26 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ * @flow
+ **/
+type Options = {
+  retries: number,
+};
+
+class Fetcher<T> {
+  options: Options;
+
+  constructor(options: Options) {
+    this.options = options;
+  }
+
+  async fetch(url: string, parse: (body: string) => T): Promise<T> {
+    return parse(`${url} after ${this.options.retries} retries`);
+  }
+}
+
+function toArray<T>(items: Array<T> | T): Array<T> {
+  return Array.isArray(items) ? items : [items];
+}
+
+module.exports = { Fetcher, toArray };
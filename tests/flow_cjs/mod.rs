@@ -0,0 +1,25 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn flow_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "fetch",
+                    test_module_matcher(),
+                    FunctionQuery::class_method("Fetcher", "fetch", FunctionKind::Async),
+                ),
+                InstrumentationConfig::new(
+                    "toArray",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("toArray", FunctionKind::Sync),
+                ),
+            ],
+            None,
+        ),
+    );
+}
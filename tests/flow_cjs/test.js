@@ -0,0 +1,18 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Fetcher, toArray } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const fetchContext = getContext('orchestrion:undici:fetch');
+const toArrayContext = getContext('orchestrion:undici:toArray');
+
+(async () => {
+  const fetcher = new Fetcher({ retries: 2 });
+  const body = await fetcher.fetch('https://example.com', (body) => body.toUpperCase());
+  assert.strictEqual(body, 'HTTPS://EXAMPLE.COM AFTER 2 RETRIES');
+  assert.deepStrictEqual(fetchContext, { start: true, end: true, asyncStart: body, asyncEnd: body });
+
+  assert.deepStrictEqual(toArray(1), [1]);
+  assert.deepStrictEqual(toArrayContext, { start: true, end: true });
+})();
@@ -0,0 +1,18 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+export async function fetch (url) {
+  await null;
+  return `fetched ${url}`;
+}
+
+export function parse (text) {
+  return JSON.parse(text);
+}
+
+export class Client {
+  constructor (host) {
+    this.host = host;
+  }
+}
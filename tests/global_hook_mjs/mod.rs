@@ -0,0 +1,31 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn global_hook_mjs() {
+    transpile_and_test(
+        file!(),
+        true,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "fetch",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("fetch", FunctionKind::Async),
+                ),
+                InstrumentationConfig::new(
+                    "parse",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("parse", FunctionKind::Sync),
+                ),
+                InstrumentationConfig::new(
+                    "Client",
+                    test_module_matcher(),
+                    FunctionQuery::class_constructor("Client"),
+                ),
+            ],
+            None,
+        )
+        .with_backend(TracingBackend::global_hook()),
+    );
+}
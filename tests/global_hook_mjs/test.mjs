@@ -0,0 +1,58 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+import { readFileSync } from 'fs';
+import { fetch, parse, Client } from './instrumented.mjs';
+import { assert } from '../common/preamble.js';
+
+// Nothing but the global object is needed.
+const source = readFileSync(new URL('./instrumented.mjs', import.meta.url), 'utf8');
+assert.ok(!source.includes('diagnostics_channel'));
+
+// Without hooks, the functions run as they are.
+assert.strictEqual(await fetch('/a'), 'fetched /a');
+
+const events = [];
+const record = (name) => ({
+  start (context) {
+    events.push([name, 'start', context.arguments[0]]);
+  },
+  end (context) {
+    events.push([name, 'end', context.result ?? context.self?.host]);
+  },
+  asyncEnd (context) {
+    events.push([name, 'asyncEnd', context.result]);
+  },
+  error (context) {
+    events.push([name, 'error', context.error.name]);
+  }
+});
+// Hooks can be registered after the functions were loaded.
+const hooks = globalThis[Symbol.for('orchestrion')] = {
+  'orchestrion:undici:fetch': record('fetch'),
+  'orchestrion:undici:parse': record('parse'),
+  'orchestrion:undici:Client': record('Client')
+};
+
+assert.strictEqual(await fetch('/b'), 'fetched /b');
+assert.deepStrictEqual(parse('{"a":1}'), { a: 1 });
+assert.throws(() => parse('{'), SyntaxError);
+assert.strictEqual(new Client('localhost').host, 'localhost');
+assert.deepStrictEqual(events, [
+  ['fetch', 'start', '/b'],
+  ['fetch', 'end', undefined],
+  ['fetch', 'asyncEnd', 'fetched /b'],
+  ['parse', 'start', '{"a":1}'],
+  ['parse', 'end', { a: 1 }],
+  ['parse', 'start', '{'],
+  ['parse', 'error', 'SyntaxError'],
+  ['parse', 'end', undefined],
+  ['Client', 'start', 'localhost'],
+  ['Client', 'end', 'localhost']
+]);
+
+delete hooks['orchestrion:undici:parse'];
+events.length = 0;
+assert.deepStrictEqual(parse('[]'), []);
+assert.deepStrictEqual(events, []);
@@ -0,0 +1,63 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function fetch (url) {
+  return { url, self: this, count: arguments.length };
+}
+
+function Connection (host) {
+  this.host = host;
+}
+
+class Base {
+  describe () {
+    return 'base';
+  }
+}
+
+class Client extends Base {
+  constructor (host) {
+    super();
+    this.host = host;
+  }
+
+  async request (path) {
+    await null;
+    return this.host + path;
+  }
+
+  mode () {
+    return this;
+  }
+
+  describe () {
+    return 'client of ' + super.describe();
+  }
+}
+
+function createAgent (prefix) {
+  let calls = 0;
+  return {
+    get (path) {
+      calls++;
+      return prefix + path + ' #' + calls;
+    }
+  };
+}
+
+const createPinger = (name) => ({
+  ping () {
+    return 'pong from ' + name;
+  }
+});
+
+const countdown = function count (n) {
+  return n && count(n - 1);
+};
+
+const factorial = function (n) {
+  return n <= 1 ? 1 : n * arguments.callee(n - 1);
+};
+
+module.exports = { fetch, Connection, Client, createAgent, createPinger, countdown, factorial };
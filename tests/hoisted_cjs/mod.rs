@@ -0,0 +1,25 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn hoisted_cjs() {
+    let hoisted = |config: InstrumentationConfig| config.with_codegen(Codegen::Hoisted);
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                configured("fetch", "fetch():sync", hoisted),
+                configured("Connection", "Connection():sync", hoisted),
+                configured("request", "Client#request:async", hoisted),
+                configured("mode", "Client#mode:sync", hoisted),
+                configured("describe", "Client#describe:sync", hoisted),
+                configured("get", "{}.get:sync where within createAgent", hoisted),
+                configured("ping", "{}.ping:sync", hoisted),
+                configured("countdown", "countdown = function():sync", hoisted),
+                configured("factorial", "factorial = function():sync", hoisted),
+            ],
+            None,
+        ),
+    );
+}
@@ -0,0 +1,76 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const {
+  fetch,
+  Connection,
+  Client,
+  createAgent,
+  createPinger,
+  countdown,
+  factorial
+} = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const { readFileSync } = require('fs');
+
+const source = readFileSync(`${__dirname}/instrumented.js`, 'utf8');
+// Only the method using `super`, the one that can't be hoisted out of an arrow function's
+// expression body, and the function expressions referring to themselves keep the closure.
+assert.strictEqual(source.match(/__apm\$traced = /g).length, 4);
+
+(async () => {
+  // Nothing is subscribed yet, so the original body is called directly.
+  const target = {};
+  assert.deepStrictEqual(fetch.call(target, '/a', 'extra'), { url: '/a', self: target, count: 2 });
+  assert.strictEqual(fetch.length, 1);
+
+  const fetchContext = getContext('orchestrion:undici:fetch');
+  assert.deepStrictEqual(fetch.call(target, '/b'), { url: '/b', self: target, count: 1 });
+  assert.deepStrictEqual(fetchContext, { start: true, end: true });
+
+  const connectionContext = getContext('orchestrion:undici:Connection');
+  const connection = new Connection('localhost');
+  assert.ok(connection instanceof Connection);
+  assert.strictEqual(connection.host, 'localhost');
+  assert.deepStrictEqual(connectionContext, { start: true, end: true });
+
+  const requestContext = getContext('orchestrion:undici:request');
+  const client = new Client('https://example.com');
+  assert.strictEqual(await client.request('/users'), 'https://example.com/users');
+  assert.deepStrictEqual(requestContext, {
+    start: true,
+    end: true,
+    asyncStart: 'https://example.com/users',
+    asyncEnd: 'https://example.com/users'
+  });
+
+  // Methods stay strict mode code, so `this` isn't boxed.
+  const modeContext = getContext('orchestrion:undici:mode');
+  assert.strictEqual(client.mode.call(5), 5);
+  assert.strictEqual(client.mode.call(undefined), undefined);
+  assert.deepStrictEqual(modeContext, { start: true, end: true });
+
+  const describeContext = getContext('orchestrion:undici:describe');
+  assert.strictEqual(client.describe(), 'client of base');
+  assert.deepStrictEqual(describeContext, { start: true, end: true });
+
+  // Hoisted functions stay in the scope of the function they were hoisted out of.
+  const getContext_ = getContext('orchestrion:undici:get');
+  const agent = createAgent('https://example.com');
+  assert.strictEqual(agent.get('/a'), 'https://example.com/a #1');
+  assert.strictEqual(agent.get('/b'), 'https://example.com/b #2');
+  assert.deepStrictEqual(getContext_, { start: true, end: true });
+
+  const pingContext = getContext('orchestrion:undici:ping');
+  assert.strictEqual(createPinger('server').ping(), 'pong from server');
+  assert.deepStrictEqual(pingContext, { start: true, end: true });
+
+  const countdownContext = getContext('orchestrion:undici:countdown');
+  assert.strictEqual(countdown(3), 0);
+  assert.deepStrictEqual(countdownContext, { start: true, end: true });
+
+  const factorialContext = getContext('orchestrion:undici:factorial');
+  assert.strictEqual(factorial(4), 24);
+  assert.deepStrictEqual(factorialContext, { start: true, end: true });
+})();
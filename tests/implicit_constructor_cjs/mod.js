@@ -0,0 +1,23 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+class Dispatcher {
+  closed = false;
+
+  close () {
+    this.closed = true;
+  }
+}
+
+class Client {
+  constructor (url) {
+    this.url = url;
+  }
+}
+
+class Pool extends Client {
+  connections = 0;
+}
+
+module.exports = { Dispatcher, Client, Pool };
@@ -0,0 +1,25 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn implicit_constructor_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "Dispatcher_constructor",
+                    test_module_matcher(),
+                    FunctionQuery::class_constructor("Dispatcher"),
+                ),
+                InstrumentationConfig::new(
+                    "Pool_constructor",
+                    test_module_matcher(),
+                    FunctionQuery::class_constructor("Pool"),
+                ),
+            ],
+            None,
+        ),
+    );
+}
@@ -0,0 +1,35 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Dispatcher, Pool } = require('./instrumented.js');
+const { assert } = require('../common/preamble.js');
+const { tracingChannel } = require('diagnostics_channel');
+
+function record (channelName) {
+  const events = [];
+  tracingChannel(channelName).subscribe({
+    start (ctx) { events.push(['start', [...ctx.arguments]]); },
+    end (ctx) { events.push(['end', ctx.self]); },
+    asyncStart () {},
+    asyncEnd () {}
+  });
+  return events;
+}
+
+const dispatcherEvents = record('orchestrion:undici:Dispatcher_constructor');
+const poolEvents = record('orchestrion:undici:Pool_constructor');
+
+const dispatcher = new Dispatcher();
+assert.strictEqual(dispatcher.closed, false);
+dispatcher.close();
+assert.strictEqual(dispatcher.closed, true);
+assert.deepStrictEqual(dispatcherEvents, [['start', []], ['end', dispatcher]]);
+
+// The synthesized constructor passes its arguments on to the superclass, and fields are still
+// initialized once it returns.
+const pool = new Pool('https://example.com');
+assert.strictEqual(pool.url, 'https://example.com');
+assert.strictEqual(pool.connections, 0);
+assert.deepStrictEqual(poolEvents, [['start', ['https://example.com']], ['end', pool]]);
+assert.strictEqual(Pool.length, 0);
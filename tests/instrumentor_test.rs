@@ -4,16 +4,47 @@
  **/
 mod common;
 
+mod analyzed_index_cjs;
+mod around_cjs;
+mod babel_class_cjs;
 mod class_method_cjs;
 mod constructor_cjs;
+mod constructor_function_cjs;
 mod constructor_mjs;
+mod context_builder_cjs;
 mod decl_cjs;
 mod decl_mjs;
+mod decorator_cjs;
+mod derived_constructor_cjs;
+mod directive_cjs;
+mod downlevel_async_cjs;
+mod export_cjs;
+mod export_mjs;
 mod expr_cjs;
 mod expr_mjs;
+mod fallback_cjs;
+mod flow_cjs;
+mod global_hook_mjs;
+mod hoisted_cjs;
+mod implicit_constructor_cjs;
 mod index_cjs;
+mod jsx_cjs;
+mod minified_cjs;
 mod multiple_class_method_cjs;
 mod multiple_load_cjs;
+mod multiple_query_cjs;
+mod nested_index_cjs;
+mod nested_mixed_cjs;
 mod object_method_cjs;
+mod otel_cjs;
+mod pattern_cjs;
 mod polyfill_cjs;
 mod polyfill_mjs;
+mod query_string_cjs;
+mod rebind_cjs;
+mod scope_cjs;
+mod selector_cjs;
+mod signature_cjs;
+mod site_cjs;
+mod template_cjs;
+mod typescript_cjs;
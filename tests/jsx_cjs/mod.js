@@ -0,0 +1,25 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function Title({ children }) {
+  return <h1 className="title">{children}</h1>;
+}
+
+async function renderPage(name) {
+  const user = await Promise.resolve({ name });
+  return (
+    <main>
+      <Title>Hello {user.name}</Title>
+      <>{[1, 2].map((n) => <span key={n}>{n}</span>)}</>
+    </main>
+  );
+}
+
+class Layout {
+  render(page) {
+    return <div id="root">{page}</div>;
+  }
+}
+
+module.exports = { renderPage, Layout };
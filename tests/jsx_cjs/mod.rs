@@ -0,0 +1,25 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn jsx_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "renderPage",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("renderPage", FunctionKind::Async),
+                ),
+                InstrumentationConfig::new(
+                    "render",
+                    test_module_matcher(),
+                    FunctionQuery::class_method("Layout", "render", FunctionKind::Sync),
+                ),
+            ],
+            None,
+        ),
+    );
+}
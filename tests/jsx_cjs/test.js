@@ -0,0 +1,26 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+global.React = {
+  Fragment: 'Fragment',
+  createElement: (type, props, ...children) => ({ type, props, children }),
+};
+const { renderPage, Layout } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const renderPageContext = getContext('orchestrion:undici:renderPage');
+const renderContext = getContext('orchestrion:undici:render');
+
+(async () => {
+  const page = await renderPage('Ada');
+  assert.strictEqual(page.type, 'main');
+  assert.strictEqual(page.children[0].type.name, 'Title');
+  assert.deepStrictEqual(page.children[0].children, ['Hello ', 'Ada']);
+  assert.strictEqual(page.children[1].type, 'Fragment');
+  assert.deepStrictEqual(renderPageContext.start, true);
+  assert.deepStrictEqual(renderPageContext.asyncEnd, page);
+
+  const root = new Layout().render(page);
+  assert.deepStrictEqual(root, { type: 'div', props: { id: 'root' }, children: [page] });
+  assert.deepStrictEqual(renderContext, { start: true, end: true });
+})();
@@ -0,0 +1,5 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function n(t,e){return e||(e={}),t+(e.method||"GET")}function r(t){return t*2}function i(t){return t+1}function o(t,e){if(e===void 0)e={retry:!0,timeout:1e3,limit:1/0};return e.retry===!1?t:t+e.timeout}module.exports={fetch:n,double:r,inc:i,request:o};
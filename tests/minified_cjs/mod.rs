@@ -0,0 +1,43 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn minified_cjs() {
+    let reference = parse_program(file!(), "reference.js");
+    let fingerprints = function_fingerprints(&reference);
+    let fetch = fingerprints
+        .iter()
+        .find(|function| function.name.as_deref() == Some("fetch"))
+        .expect("Couldn't find fetch in the reference build");
+    // Written with the literals minifiers replace, such as `true` for `!0` and `1000` for `1e3`.
+    let request = fingerprints
+        .iter()
+        .find(|function| function.name.as_deref() == Some("request"))
+        .expect("Couldn't find request in the reference build");
+    assert_eq!(fingerprints.len(), 4);
+
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "fetch",
+                    test_module_matcher(),
+                    FunctionQuery::fingerprint(&fetch.fingerprint, FunctionKind::Sync),
+                ),
+                InstrumentationConfig::new(
+                    "request",
+                    test_module_matcher(),
+                    FunctionQuery::fingerprint(&request.fingerprint, FunctionKind::Sync),
+                ),
+                InstrumentationConfig::new(
+                    "located_{name}",
+                    test_module_matcher(),
+                    FunctionQuery::location(SourceRange::at(5, 79), FunctionKind::Sync),
+                ),
+            ],
+            None,
+        ),
+    );
+}
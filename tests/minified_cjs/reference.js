@@ -0,0 +1,22 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function fetch(url, options) {
+  return options || (options = {}), url + (options.method || 'GET');
+}
+
+function double(value) {
+  return value * 2;
+}
+
+function inc(value) {
+  return value + 1;
+}
+
+function request(url, options) {
+  if (options === undefined) options = { retry: true, timeout: 1000, limit: Infinity };
+  return options.retry === false ? url : url + options.timeout;
+}
+
+module.exports = { fetch, double, inc, request };
@@ -0,0 +1,23 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { fetch, double, inc, request } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const fetchContext = getContext('orchestrion:undici:fetch');
+const incContext = getContext('orchestrion:undici:located_i');
+
+assert.strictEqual(double(2), 4);
+assert.deepStrictEqual(fetchContext, {});
+assert.deepStrictEqual(incContext, {});
+
+assert.strictEqual(fetch('https://example.com'), 'https://example.comGET');
+assert.deepStrictEqual(fetchContext, { start: true, end: true });
+assert.deepStrictEqual(incContext, {});
+
+assert.strictEqual(inc(1), 2);
+assert.deepStrictEqual(incContext, { start: true, end: true });
+
+const requestContext = getContext('orchestrion:undici:request');
+assert.strictEqual(request('/a'), '/a1000');
+assert.deepStrictEqual(requestContext, { start: true, end: true });
@@ -0,0 +1,23 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+class Client {
+  query (sql) {
+    return 'client';
+  }
+}
+
+class Pool {
+  query (sql) {
+    return 'pool';
+  }
+}
+
+class Connection {
+  query (sql) {
+    return 'connection';
+  }
+}
+
+module.exports = { Client, Pool, Connection };
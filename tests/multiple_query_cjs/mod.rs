@@ -0,0 +1,25 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn multiple_query_cjs() {
+    assert!(
+        InstrumentationConfig::with_queries("query", test_module_matcher(), vec![])
+            .validate()
+            .is_err()
+    );
+
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new_single(InstrumentationConfig::with_queries(
+            "query",
+            test_module_matcher(),
+            vec![
+                FunctionQuery::class_method("Client", "query", FunctionKind::Sync),
+                FunctionQuery::class_method("Pool", "query", FunctionKind::Sync),
+                FunctionQuery::class_method("Connection", "query", FunctionKind::Sync),
+            ],
+        )),
+    );
+}
@@ -0,0 +1,23 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Client, Pool, Connection } = require('./instrumented.js');
+const { tracingChannel } = require('diagnostics_channel');
+const { assert } = require('../common/preamble.js');
+
+const results = [];
+tracingChannel('orchestrion:undici:query').subscribe({
+  start (message) {
+    results.push(message.self.constructor.name);
+  },
+  end () {},
+  asyncStart () {},
+  asyncEnd () {},
+  error () {}
+});
+
+assert.strictEqual(new Client().query('SELECT 1'), 'client');
+assert.strictEqual(new Pool().query('SELECT 1'), 'pool');
+assert.strictEqual(new Connection().query('SELECT 1'), 'connection');
+assert.deepStrictEqual(results, ['Client', 'Pool', 'Connection']);
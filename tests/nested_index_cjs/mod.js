@@ -0,0 +1,29 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function createAgent () {
+  function fetch () {
+    return 'nested';
+  }
+  return { fetch, get () { return 'nested'; } };
+}
+
+exports.createAgent = createAgent;
+
+{
+  function fetch () {
+    return 0;
+  }
+
+  exports.fetch0 = fetch;
+  exports.agent0 = { get () { return 0; } };
+}
+{
+  function fetch () {
+    return 1;
+  }
+
+  exports.fetch1 = fetch;
+  exports.agent1 = { get () { return 1; } };
+}
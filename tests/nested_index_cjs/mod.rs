@@ -0,0 +1,33 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn nested_index_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "fetch",
+                    test_module_matcher(),
+                    FunctionQuery::FunctionDeclaration {
+                        function_name: "fetch".to_string(),
+                        kind: FunctionKind::Sync,
+                        index: 1,
+                    },
+                ),
+                InstrumentationConfig::new(
+                    "get",
+                    test_module_matcher(),
+                    FunctionQuery::ObjectMethod {
+                        method_name: "get".to_string(),
+                        kind: FunctionKind::Sync,
+                        index: 1,
+                    },
+                ),
+            ],
+            None,
+        ),
+    );
+}
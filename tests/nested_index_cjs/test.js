@@ -0,0 +1,25 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { createAgent, fetch0, fetch1, agent0, agent1 } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const fetchContext = getContext('orchestrion:undici:fetch');
+const getContext_ = getContext('orchestrion:undici:get');
+
+// Functions nested within other functions don't count towards the index of unscoped queries.
+const agent = createAgent();
+assert.strictEqual(agent.fetch(), 'nested');
+assert.strictEqual(agent.get(), 'nested');
+assert.deepStrictEqual(fetchContext, {});
+assert.deepStrictEqual(getContext_, {});
+
+assert.strictEqual(fetch0(), 0);
+assert.strictEqual(agent0.get(), 0);
+assert.deepStrictEqual(fetchContext, {});
+assert.deepStrictEqual(getContext_, {});
+
+assert.strictEqual(fetch1(), 1);
+assert.strictEqual(agent1.get(), 1);
+assert.deepStrictEqual(fetchContext, { start: true, end: true });
+assert.deepStrictEqual(getContext_, { start: true, end: true });
@@ -0,0 +1,25 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn nested_mixed_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "createAgent",
+                    test_module_matcher(),
+                    FunctionQuery::exported("createAgent", FunctionKind::Sync),
+                ),
+                InstrumentationConfig::new(
+                    "fetch",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("fetch", FunctionKind::Sync),
+                ),
+            ],
+            None,
+        ),
+    );
+}
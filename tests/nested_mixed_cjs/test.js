@@ -0,0 +1,18 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { createAgent, fetch } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const createAgentContext = getContext('orchestrion:undici:createAgent');
+const fetchContext = getContext('orchestrion:undici:fetch');
+
+// Queries matched through the analysis look inside functions, but the name-only query must
+// still only see top-level functions.
+const agent = createAgent();
+assert.deepStrictEqual(createAgentContext, { start: true, end: true });
+assert.strictEqual(agent.fetch(), 'nested');
+assert.deepStrictEqual(fetchContext, {});
+
+assert.strictEqual(fetch(), 'top-level');
+assert.deepStrictEqual(fetchContext, { start: true, end: true });
@@ -0,0 +1,23 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+async function fetch (url) {
+  await null;
+  if (url === '/missing') {
+    throw new Error('not found');
+  }
+  return `fetched ${url}`;
+}
+
+function parse (text) {
+  return JSON.parse(text);
+}
+
+class Client {
+  constructor (host) {
+    this.host = host;
+  }
+}
+
+module.exports = { fetch, parse, Client };
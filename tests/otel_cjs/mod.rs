@@ -0,0 +1,34 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn otel_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "fetch",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("fetch", FunctionKind::Async),
+                ),
+                InstrumentationConfig::new(
+                    "parse",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("parse", FunctionKind::Sync),
+                ),
+                InstrumentationConfig::new(
+                    "Client",
+                    test_module_matcher(),
+                    FunctionQuery::class_constructor("Client"),
+                ),
+            ],
+            None,
+        )
+        .with_backend(TracingBackend::OpenTelemetry {
+            module: "./otel.js".to_string(),
+            tracer_name: "orchestrion-test".to_string(),
+        }),
+    );
+}
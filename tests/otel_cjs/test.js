@@ -0,0 +1,43 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { fetch, parse, Client } = require('./instrumented.js');
+const { spans } = require('./otel.js');
+const { assert } = require('../common/preamble.js');
+
+const summary = () => spans.map(({ tracer, name, ended, exceptions, status }) => ({
+  tracer,
+  name,
+  ended,
+  exceptions,
+  status
+}));
+
+(async () => {
+  assert.strictEqual(await fetch('/a'), 'fetched /a');
+  await assert.rejects(fetch('/missing'), /not found/);
+  assert.deepStrictEqual(parse('{"a":1}'), { a: 1 });
+  assert.throws(() => parse('{'), SyntaxError);
+  assert.strictEqual(new Client('localhost').host, 'localhost');
+
+  const ok = { tracer: 'orchestrion-test', ended: true, exceptions: [], status: null };
+  assert.deepStrictEqual(summary(), [
+    { ...ok, name: 'orchestrion:undici:fetch' },
+    {
+      ...ok,
+      name: 'orchestrion:undici:fetch',
+      exceptions: ['not found'],
+      status: { code: 2, message: 'not found' }
+    },
+    { ...ok, name: 'orchestrion:undici:parse' },
+    {
+      ...ok,
+      name: 'orchestrion:undici:parse',
+      exceptions: [spans[3].exceptions[0]],
+      status: { code: 2, message: spans[3].exceptions[0] }
+    },
+    { ...ok, name: 'orchestrion:undici:Client' }
+  ]);
+  assert.strictEqual(spans[3].exceptions.length, 1);
+})();
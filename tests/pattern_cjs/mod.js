@@ -0,0 +1,31 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+class Client {
+  async query (sql) {
+    return 'query';
+  }
+
+  async execute (sql) {
+    return 'execute';
+  }
+
+  async _private () {
+    return 'private';
+  }
+}
+
+function get (key) {
+  return 'get';
+}
+
+function set (key) {
+  return 'set';
+}
+
+function reset () {
+  return 'reset';
+}
+
+module.exports = { Client, get, set, reset };
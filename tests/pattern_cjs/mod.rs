@@ -0,0 +1,35 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn pattern_cjs() {
+    let class_query = FunctionQuery::class_method("Cli*", "/^[^_]/", FunctionKind::Async);
+    let function_query = FunctionQuery::function_declaration("/^(get|set)$/", FunctionKind::Sync);
+    class_query.validate().unwrap();
+    function_query.validate().unwrap();
+    assert!(
+        FunctionQuery::function_declaration("/(/", FunctionKind::Sync)
+            .validate()
+            .is_err()
+    );
+    assert!(
+        Instrumentor::new(Config::new_single(InstrumentationConfig::new(
+            "fn_{name}",
+            test_module_matcher(),
+            FunctionQuery::function_declaration("/(/", FunctionKind::Sync),
+        )))
+        .is_err()
+    );
+
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new("{class}_{name}", test_module_matcher(), class_query),
+                InstrumentationConfig::new("fn_{name}", test_module_matcher(), function_query),
+            ],
+            None,
+        ),
+    );
+}
@@ -0,0 +1,40 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Client, get, set, reset } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const queryContext = getContext('orchestrion:undici:Client_query');
+const executeContext = getContext('orchestrion:undici:Client_execute');
+const privateContext = getContext('orchestrion:undici:Client__private');
+const getFnContext = getContext('orchestrion:undici:fn_get');
+const setFnContext = getContext('orchestrion:undici:fn_set');
+const resetFnContext = getContext('orchestrion:undici:fn_reset');
+
+(async () => {
+  const client = new Client();
+  assert.strictEqual(await client.query(), 'query');
+  assert.deepStrictEqual(queryContext, {
+    start: true,
+    end: true,
+    asyncStart: 'query',
+    asyncEnd: 'query'
+  });
+  assert.deepStrictEqual(executeContext, {});
+  assert.strictEqual(await client.execute(), 'execute');
+  assert.deepStrictEqual(executeContext, {
+    start: true,
+    end: true,
+    asyncStart: 'execute',
+    asyncEnd: 'execute'
+  });
+  assert.strictEqual(await client._private(), 'private');
+  assert.deepStrictEqual(privateContext, {});
+
+  assert.strictEqual(get(), 'get');
+  assert.strictEqual(set(), 'set');
+  assert.strictEqual(reset(), 'reset');
+  assert.deepStrictEqual(getFnContext, { start: true, end: true });
+  assert.deepStrictEqual(setFnContext, { start: true, end: true });
+  assert.deepStrictEqual(resetFnContext, {});
+})();
@@ -0,0 +1,32 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+class Undici {
+  constructor (url) {
+    this.url = url;
+  }
+
+  async fetch (path) {
+    return this.url + path;
+  }
+}
+
+function fetch (url) {
+  return 'first';
+}
+
+function fetch2 () {
+  function fetch (url, opts) {
+    return 'second';
+  }
+  return fetch('https://example.com');
+}
+
+const client = {
+  async query (sql) {
+    return 'query';
+  }
+};
+
+module.exports = { Undici, fetch, fetch2, client };
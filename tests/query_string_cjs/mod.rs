@@ -0,0 +1,158 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+fn query(short: &str) -> FunctionQuery {
+    let query: FunctionQuery = short.parse().unwrap();
+    assert_eq!(query.to_string(), short);
+    query
+}
+
+/// Every kind of query, with each of these names wherever the query has a name.
+fn queries_named(name: &str) -> Vec<FunctionQuery> {
+    let base = [
+        FunctionQuery::ClassConstructor {
+            class_name: name.to_string(),
+            index: 0,
+        },
+        FunctionQuery::FunctionConstructor {
+            function_name: name.to_string(),
+            index: 1,
+        },
+        FunctionQuery::ClassMethod {
+            class_name: name.to_string(),
+            method_name: name.to_string(),
+            kind: FunctionKind::Async,
+            index: 2,
+        },
+        FunctionQuery::object_method(name, FunctionKind::Sync),
+        FunctionQuery::function_declaration(name, FunctionKind::Async),
+        FunctionQuery::function_expression(name, FunctionKind::Sync),
+        FunctionQuery::exported(name, FunctionKind::Async),
+        FunctionQuery::fingerprint(name, FunctionKind::Sync),
+        FunctionQuery::location(SourceRange::new(5, 79, 6, 1), FunctionKind::Async),
+        FunctionQuery::selector(
+            "Class[name=\"a | b\"] > Method[name='x where y']"
+                .parse()
+                .unwrap(),
+            FunctionKind::Sync,
+        ),
+    ];
+    let mut queries = vec![];
+    for query in base {
+        let signature = FunctionSignature {
+            has_rest: Some(false),
+            ..FunctionSignature::params(&[name, ""])
+        };
+        queries.push(query.clone().in_scope(FunctionScope {
+            top_level: true,
+            enclosing: vec![name.to_string(), "request".to_string()],
+            unshadowed: true,
+        }));
+        queries.push(query.clone().with_signature(signature));
+        queries.push(query.clone().with_decorator(DecoratorQuery::named(name)));
+        queries.push(
+            query
+                .clone()
+                .with_decorator(DecoratorQuery::named("Get").with_args(&[name, "", "1"])),
+        );
+        queries.push(FunctionQuery::first_of(vec![
+            query.clone(),
+            FunctionQuery::function_declaration(name, FunctionKind::Sync),
+        ]));
+        queries.push(query);
+    }
+    queries
+}
+
+#[test]
+fn query_string_cjs() {
+    for name in [
+        "fetch",
+        "#fetch",
+        "Cli*",
+        "/^(get|set)$/",
+        "/^[^_]/",
+        "/a | b/",
+        "/\\d+/",
+        "a where b",
+        "a, b",
+        "a > b",
+        "a()",
+        "a[1]",
+        "a:sync",
+        "new a",
+        "{}.a",
+        "say \"hi\"",
+        "it's",
+        " padded ",
+    ] {
+        for query in queries_named(name) {
+            let short = query.to_string();
+            let parsed: FunctionQuery = short
+                .parse()
+                .unwrap_or_else(|e| panic!("Couldn't parse {short:?}: {e}"));
+            assert_eq!(format!("{parsed:?}"), format!("{query:?}"), "{short}");
+        }
+    }
+    assert_eq!(
+        FunctionQuery::function_declaration("/a | b/", FunctionKind::Sync).to_string(),
+        "\"/a | b/\"():sync"
+    );
+
+    for short in [
+        "Undici#fetch:async[2]",
+        "fetch = function():sync",
+        "new Connection()[1]",
+        "export Undici2.fetch:sync",
+        "at 5:79-6:1:sync",
+        "fingerprint 1a2b3c4d5e6f7a8b:async",
+        "select Class[name=Undici] > Method[name=fetch]:async",
+        "fetch():sync where within createClient > request, unshadowed, params(url, opts), !rest",
+    ] {
+        query(short);
+    }
+    assert_eq!(
+        "fetch()".parse::<FunctionQuery>().unwrap().to_string(),
+        "fetch():sync"
+    );
+    assert!("Undici".parse::<FunctionQuery>().is_err());
+    assert!("new Undici:async".parse::<FunctionQuery>().is_err());
+    assert!("fetch():sync where nested"
+        .parse::<FunctionQuery>()
+        .is_err());
+    assert!("fetch():eventually".parse::<FunctionQuery>().is_err());
+    assert_eq!(
+        "async".parse::<FunctionKind>().unwrap().to_string(),
+        "async"
+    );
+
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "Undici_constructor",
+                    test_module_matcher(),
+                    query("new Undici"),
+                ),
+                InstrumentationConfig::new(
+                    "Undici_fetch",
+                    test_module_matcher(),
+                    query("Undici#fetch:async"),
+                ),
+                InstrumentationConfig::new(
+                    "fetch_decl",
+                    test_module_matcher(),
+                    query("fetch():sync where top-level"),
+                ),
+                InstrumentationConfig::new(
+                    "client_query",
+                    test_module_matcher(),
+                    query("{}.query:async"),
+                ),
+            ],
+            None,
+        ),
+    );
+}
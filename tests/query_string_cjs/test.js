@@ -0,0 +1,36 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Undici, fetch, fetch2, client } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const constructorContext = getContext('orchestrion:undici:Undici_constructor');
+const fetchContext = getContext('orchestrion:undici:Undici_fetch');
+const declContext = getContext('orchestrion:undici:fetch_decl');
+const queryContext = getContext('orchestrion:undici:client_query');
+
+(async () => {
+  const undici = new Undici('https://example.com');
+  assert.deepStrictEqual(constructorContext, { start: true, end: true });
+
+  assert.strictEqual(await undici.fetch('/path'), 'https://example.com/path');
+  assert.deepStrictEqual(fetchContext, {
+    start: true,
+    end: true,
+    asyncStart: 'https://example.com/path',
+    asyncEnd: 'https://example.com/path'
+  });
+
+  assert.strictEqual(fetch2(), 'second');
+  assert.deepStrictEqual(declContext, {});
+  assert.strictEqual(fetch('https://example.com'), 'first');
+  assert.deepStrictEqual(declContext, { start: true, end: true });
+
+  assert.strictEqual(await client.query('SELECT 1'), 'query');
+  assert.deepStrictEqual(queryContext, {
+    start: true,
+    end: true,
+    asyncStart: 'query',
+    asyncEnd: 'query'
+  });
+})();
@@ -0,0 +1,33 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function fetch (url, { method = 'GET' } = {}, ...rest) {
+  return { url, method, rest };
+}
+
+async function send (message) {
+  await null;
+  return message;
+}
+
+class Client {
+  constructor (host, port = 80) {
+    this.host = host;
+    this.port = port;
+  }
+}
+
+function Connection (host) {
+  this.host = host;
+}
+
+function query (sql, values) {
+  return { sql, values };
+}
+
+function unchanged (value) {
+  return value;
+}
+
+module.exports = { fetch, send, Client, Connection, query, unchanged };
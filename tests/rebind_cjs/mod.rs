@@ -0,0 +1,26 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn rebind_cjs() {
+    let rebound = InstrumentationConfig::with_rebound_parameters;
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                configured("fetch", "fetch():sync", rebound),
+                configured("send", "send():async", rebound),
+                configured("Client", "new Client", rebound),
+                configured("Connection", "new Connection()", rebound),
+                configured("query", "query():sync", rebound).with_codegen(Codegen::Hoisted),
+                InstrumentationConfig::new(
+                    "unchanged",
+                    test_module_matcher(),
+                    "unchanged():sync".parse().unwrap(),
+                ),
+            ],
+            None,
+        ),
+    );
+}
@@ -0,0 +1,58 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { fetch, send, Client, Connection, query, unchanged } = require('./instrumented.js');
+const { assert } = require('../common/preamble.js');
+const { tracingChannel } = require('diagnostics_channel');
+
+function rewrite (channelName, rewriter) {
+  tracingChannel(channelName).subscribe({
+    start (message) {
+      rewriter(message);
+    },
+    end () {},
+    asyncStart () {},
+    asyncEnd () {},
+    error () {}
+  });
+}
+
+(async () => {
+  // Nothing is subscribed yet, so the arguments are used as they were passed.
+  assert.deepStrictEqual(fetch('/a'), { url: '/a', method: 'GET', rest: [] });
+
+  rewrite('orchestrion:undici:fetch', (message) => {
+    message.arguments = ['/b', { method: 'POST' }, 'extra'];
+  });
+  assert.deepStrictEqual(fetch('/a'), { url: '/b', method: 'POST', rest: ['extra'] });
+
+  rewrite('orchestrion:undici:send', (message) => {
+    message.arguments[0] = message.arguments[0].toUpperCase();
+  });
+  assert.strictEqual(await send('hello'), 'HELLO');
+
+  // Defaults of arguments that are still missing are evaluated again.
+  rewrite('orchestrion:undici:Client', (message) => {
+    message.arguments = [message.arguments[0] + '.local'];
+  });
+  const client = new Client('example', 8080);
+  assert.strictEqual(client.host, 'example.local');
+  assert.strictEqual(client.port, 80);
+
+  rewrite('orchestrion:undici:Connection', (message) => {
+    message.arguments[0] = 'rewritten';
+  });
+  assert.strictEqual(new Connection('localhost').host, 'rewritten');
+
+  rewrite('orchestrion:undici:query', (message) => {
+    message.arguments = ['SELECT 2', [2]];
+  });
+  assert.deepStrictEqual(query('SELECT 1', [1]), { sql: 'SELECT 2', values: [2] });
+
+  // Without the opt-in, rewriting the arguments has no effect.
+  rewrite('orchestrion:undici:unchanged', (message) => {
+    message.arguments = ['rewritten'];
+  });
+  assert.strictEqual(unchanged('original'), 'original');
+})();
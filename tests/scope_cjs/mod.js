@@ -0,0 +1,31 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function createClient () {
+  function request () {
+    function fetch () {
+      return 'nested';
+    }
+    return fetch();
+  }
+  return { request };
+}
+
+function fetch () {
+  return 'top';
+}
+
+let handler;
+function setup () {
+  let handler;
+  handler = function () {
+    return 'local';
+  };
+  return handler;
+}
+handler = function () {
+  return 'global';
+};
+
+module.exports = { createClient, fetch, setup, getHandler: () => handler };
@@ -0,0 +1,33 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn scope_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "fetch_top",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("fetch", FunctionKind::Sync)
+                        .in_scope(FunctionScope::top_level()),
+                ),
+                InstrumentationConfig::new(
+                    "fetch_nested",
+                    test_module_matcher(),
+                    FunctionQuery::function_declaration("fetch", FunctionKind::Sync)
+                        .in_scope(FunctionScope::within("createClient > request")),
+                ),
+                InstrumentationConfig::new(
+                    "handler",
+                    test_module_matcher(),
+                    FunctionQuery::function_expression("handler", FunctionKind::Sync)
+                        .in_scope(FunctionScope::default().unshadowed()),
+                ),
+            ],
+            None,
+        ),
+    );
+}
@@ -0,0 +1,21 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { createClient, fetch, setup, getHandler } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const topContext = getContext('orchestrion:undici:fetch_top');
+const nestedContext = getContext('orchestrion:undici:fetch_nested');
+const handlerContext = getContext('orchestrion:undici:handler');
+
+assert.strictEqual(createClient().request(), 'nested');
+assert.deepStrictEqual(topContext, {});
+assert.deepStrictEqual(nestedContext, { start: true, end: true });
+
+assert.strictEqual(fetch(), 'top');
+assert.deepStrictEqual(topContext, { start: true, end: true });
+
+assert.strictEqual(setup()(), 'local');
+assert.deepStrictEqual(handlerContext, {});
+assert.strictEqual(getHandler()(), 'global');
+assert.deepStrictEqual(handlerContext, { start: true, end: true });
@@ -0,0 +1,29 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+class Undici {
+  async fetch (url) {
+    return 'undici';
+  }
+}
+
+class Other {
+  async fetch (url) {
+    return 'other';
+  }
+}
+
+module.exports.fetch = function (url) {
+  return 'exported';
+};
+
+const helpers = {
+  fetch: function (url) {
+    return 'helper';
+  }
+};
+
+module.exports.Undici = Undici;
+module.exports.Other = Other;
+module.exports.helpers = helpers;
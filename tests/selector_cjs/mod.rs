@@ -0,0 +1,40 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn selector_cjs() {
+    assert!("Class > Method[name=fetch".parse::<Selector>().is_err());
+    assert!("Class[name=Undici]".parse::<Selector>().is_err());
+    assert!("Method[nmae=fetch]".parse::<Selector>().is_err());
+    assert!("Method[name=\"/(/\"]".parse::<Selector>().is_err());
+
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "Undici_fetch",
+                    test_module_matcher(),
+                    FunctionQuery::selector(
+                        "Class[name=Undici] > Method[name=fetch][async]"
+                            .parse()
+                            .unwrap(),
+                        FunctionKind::Async,
+                    ),
+                ),
+                InstrumentationConfig::new(
+                    "exported_{name}",
+                    test_module_matcher(),
+                    FunctionQuery::selector(
+                        "Assign[target=\"module.exports.fetch\"] > Function"
+                            .parse()
+                            .unwrap(),
+                        FunctionKind::Sync,
+                    ),
+                ),
+            ],
+            None,
+        ),
+    );
+}
@@ -0,0 +1,22 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Undici, Other, fetch, helpers } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const undiciContext = getContext('orchestrion:undici:Undici_fetch');
+const exportedContext = getContext('orchestrion:undici:exported_fetch');
+
+(async () => {
+  assert.strictEqual(await new Other().fetch('https://example.com'), 'other');
+  assert.strictEqual(helpers.fetch('https://example.com'), 'helper');
+  assert.deepStrictEqual(undiciContext, {});
+  assert.deepStrictEqual(exportedContext, {});
+
+  assert.strictEqual(await new Undici().fetch('https://example.com'), 'undici');
+  assert.deepStrictEqual(undiciContext, { start: true, end: true, asyncStart: 'undici', asyncEnd: 'undici' });
+  assert.deepStrictEqual(exportedContext, {});
+
+  assert.strictEqual(fetch('https://example.com'), 'exported');
+  assert.deepStrictEqual(exportedContext, { start: true, end: true });
+})();
@@ -0,0 +1,20 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const a = {};
+a.fetch = function (url) {
+  return 'a';
+};
+
+const b = {};
+b.fetch = function (url, opts = {}) {
+  return 'b';
+};
+
+const c = {};
+c.fetch = function (...args) {
+  return 'c';
+};
+
+module.exports = { a, b, c };
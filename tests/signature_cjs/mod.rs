@@ -0,0 +1,31 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn signature_cjs() {
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "fetch_opts",
+                    test_module_matcher(),
+                    FunctionQuery::function_expression("fetch", FunctionKind::Sync)
+                        .with_signature(FunctionSignature::params(&["url", "opts"])),
+                ),
+                InstrumentationConfig::new(
+                    "fetch_rest",
+                    test_module_matcher(),
+                    FunctionQuery::function_expression("fetch", FunctionKind::Sync).with_signature(
+                        FunctionSignature {
+                            has_rest: Some(true),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+            ],
+            None,
+        ),
+    );
+}
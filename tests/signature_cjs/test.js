@@ -0,0 +1,19 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { a, b, c } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const optsContext = getContext('orchestrion:undici:fetch_opts');
+const restContext = getContext('orchestrion:undici:fetch_rest');
+
+assert.strictEqual(a.fetch('https://example.com'), 'a');
+assert.deepStrictEqual(optsContext, {});
+assert.deepStrictEqual(restContext, {});
+
+assert.strictEqual(b.fetch('https://example.com'), 'b');
+assert.deepStrictEqual(optsContext, { start: true, end: true });
+assert.deepStrictEqual(restContext, {});
+
+assert.strictEqual(c.fetch('https://example.com'), 'c');
+assert.deepStrictEqual(restContext, { start: true, end: true });
@@ -0,0 +1,23 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function fetch (url) {
+  return url;
+}
+
+class Client {
+  constructor (host) {
+    this.host = host;
+  }
+
+  async request (path) {
+    return this.host + path;
+  }
+}
+
+function plain () {
+  return 'plain';
+}
+
+module.exports = { fetch, Client, plain };
@@ -0,0 +1,24 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn site_cjs() {
+    let site = InstrumentationConfig::with_site_metadata;
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                configured("fetch", "fetch():sync", site),
+                configured("request", "Client#request:async", site).with_codegen(Codegen::Hoisted),
+                configured("Client", "new Client", site),
+                InstrumentationConfig::new(
+                    "plain",
+                    test_module_matcher(),
+                    "plain():sync".parse().unwrap(),
+                ),
+            ],
+            None,
+        ),
+    );
+}
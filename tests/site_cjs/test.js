@@ -0,0 +1,59 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { fetch, Client, plain } = require('./instrumented.js');
+const { assert } = require('../common/preamble.js');
+const { tracingChannel } = require('diagnostics_channel');
+
+function record (channelName) {
+  const contexts = [];
+  tracingChannel(channelName).subscribe({
+    start (message) {
+      contexts.push(message);
+    },
+    end () {},
+    asyncStart () {},
+    asyncEnd () {},
+    error () {}
+  });
+  return contexts;
+}
+
+(async () => {
+  const fetchContexts = record('orchestrion:undici:fetch');
+  fetch('/a');
+  fetch('/b');
+  const [first, second] = fetchContexts;
+  assert.deepStrictEqual(first.site, {
+    module: 'undici',
+    version: '0.0.1',
+    file: 'index.mjs',
+    query: 'fetch():sync',
+    line: 5,
+    column: 1
+  });
+  assert.ok(Object.isFrozen(first.site));
+  // The metadata is declared once for each function.
+  assert.strictEqual(first.site, second.site);
+
+  const clientContexts = record('orchestrion:undici:Client');
+  const requestContexts = record('orchestrion:undici:request');
+  const client = new Client('https://example.com');
+  assert.strictEqual(await client.request('/users'), 'https://example.com/users');
+  assert.deepStrictEqual(clientContexts[0].site, {
+    module: 'undici',
+    version: '0.0.1',
+    file: 'index.mjs',
+    query: 'new Client',
+    line: 10,
+    column: 3
+  });
+  assert.strictEqual(requestContexts[0].site.query, 'Client#request:async');
+  assert.strictEqual(requestContexts[0].site.line, 14);
+
+  // Without the opt-in, there's no metadata.
+  const plainContexts = record('orchestrion:undici:plain');
+  plain();
+  assert.strictEqual(plainContexts[0].site, undefined);
+})();
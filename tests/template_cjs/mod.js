@@ -0,0 +1,20 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+function fetch (url) {
+  return `fetched ${url}`;
+}
+
+class Client {
+  constructor (host) {
+    this.host = host;
+  }
+
+  async request (path) {
+    await null;
+    return this.host + path;
+  }
+}
+
+module.exports = { fetch, Client };
@@ -0,0 +1,56 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+static SPAN_TEMPLATE: &str = "
+    const span = tracer.startSpan($channel);
+    try {
+        return $body();
+    } finally {
+        span.end();
+    }
+";
+
+static HOOK_TEMPLATE: &str = "
+    const hook = globalThis.hooks[$channel] ?? ((info, result) => result);
+    return hook({ kind: $kind, self: $self, context: $context }, $body());
+";
+
+#[test]
+fn template_cjs() {
+    assert!("return 1;".parse::<CodeTemplate>().is_err());
+    assert!("$body(); return $body();".parse::<CodeTemplate>().is_err());
+    assert!("for (;;) $body();".parse::<CodeTemplate>().is_err());
+    assert!("return $body;".parse::<CodeTemplate>().is_err());
+    assert!("[1].map(() => $body());".parse::<CodeTemplate>().is_err());
+    assert!("return $bdoy();".parse::<CodeTemplate>().is_err());
+    assert!("return $body(".parse::<CodeTemplate>().is_err());
+    assert!("}\nsteal();\nfunction f() {\n$body();"
+        .parse::<CodeTemplate>()
+        .is_err());
+    assert!("return $body(); // done".parse::<CodeTemplate>().is_ok());
+
+    let span = |config: InstrumentationConfig| config.with_template(SPAN_TEMPLATE.parse().unwrap());
+    let hook = |config: InstrumentationConfig| config.with_template(HOOK_TEMPLATE.parse().unwrap());
+    for config in [
+        configured("fetch", "fetch():sync", span).with_codegen(Codegen::Hoisted),
+        configured("fetch", "fetch():sync", span).with_rebound_parameters(),
+        configured("fetch", "fetch():sync", span).with_around_advice(),
+        configured("fetch", "fetch():sync", span).with_site_metadata(),
+        configured("client", "new Client", span),
+    ] {
+        assert!(Instrumentor::new(Config::new_single(config)).is_err());
+    }
+
+    transpile_and_test(
+        file!(),
+        false,
+        Config::new(
+            vec![
+                configured("fetch", "fetch():sync", span),
+                configured("request", "Client#request:async", hook)
+                    .with_context_builder("{ path }".parse().unwrap()),
+            ],
+            None,
+        ),
+    );
+}
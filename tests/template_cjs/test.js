@@ -0,0 +1,44 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { assert } = require('../common/preamble.js');
+const { readFileSync } = require('fs');
+
+// Templates don't publish on channels, so they're neither declared nor is `dc` required.
+const source = readFileSync(`${__dirname}/instrumented.js`, 'utf8');
+assert.ok(!source.includes('require('));
+assert.ok(!source.includes('tr_ch_apm'));
+
+const spans = [];
+globalThis.tracer = {
+  startSpan (name) {
+    const span = { name, ended: false, end () { this.ended = true; } };
+    spans.push(span);
+    return span;
+  }
+};
+globalThis.hooks = {};
+
+const { fetch, Client } = require('./instrumented.js');
+
+(async () => {
+  assert.strictEqual(fetch('/a'), 'fetched /a');
+  assert.deepStrictEqual(spans.map(({ name, ended }) => ({ name, ended })), [
+    { name: 'orchestrion:undici:fetch', ended: true }
+  ]);
+
+  // Without a hook, the original body runs as it is.
+  const client = new Client('https://example.com');
+  assert.strictEqual(await client.request('/a'), 'https://example.com/a');
+
+  const calls = [];
+  globalThis.hooks['orchestrion:undici:request'] = async ({ kind, self, context }, result) => {
+    calls.push({ kind, self, path: context.path, arguments: [...context.arguments] });
+    return `${await result}!`;
+  };
+  assert.strictEqual(await client.request('/b'), 'https://example.com/b!');
+  assert.deepStrictEqual(calls, [
+    { kind: 'async', self: client, path: '/b', arguments: ['/b'] }
+  ]);
+})();
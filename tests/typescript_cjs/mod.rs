@@ -0,0 +1,41 @@
+use crate::common::*;
+use orchestrion_js::*;
+
+#[test]
+fn typescript_cjs() {
+    transpile_and_test_ts(
+        file!(),
+        Config::new(
+            vec![
+                InstrumentationConfig::new(
+                    "send",
+                    test_module_matcher(),
+                    FunctionQuery::class_method("Client", "send", FunctionKind::Async),
+                ),
+                InstrumentationConfig::new(
+                    "client",
+                    test_module_matcher(),
+                    FunctionQuery::class_constructor("Client"),
+                ),
+                InstrumentationConfig::new(
+                    "server",
+                    test_module_matcher(),
+                    FunctionQuery::class_constructor("Server"),
+                ),
+                InstrumentationConfig::new(
+                    "describe",
+                    test_module_matcher(),
+                    "Server#describe:sync where params(verbose)"
+                        .parse()
+                        .unwrap(),
+                ),
+                InstrumentationConfig::new(
+                    "request",
+                    test_module_matcher(),
+                    "request():sync where within createAgent".parse().unwrap(),
+                ),
+            ],
+            None,
+        ),
+    );
+}
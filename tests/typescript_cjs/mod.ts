@@ -0,0 +1,54 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+interface Options {
+  retries?: number;
+}
+
+abstract class Transport {
+  declare readonly kind: string;
+
+  abstract send(body: string): Promise<string>;
+  abstract send(body: Buffer): Promise<string>;
+}
+
+class Client extends Transport {
+  constructor(host: string);
+  constructor(host: string, port: number);
+  constructor(private readonly host: string, public port: number = 80) {
+    super();
+  }
+
+  send(body: string): Promise<string>;
+  send(body: Buffer): Promise<string>;
+  public override async send(body: string | Buffer): Promise<string> {
+    return `${this.host}:${this.port} ${body}`;
+  }
+}
+
+class Server {
+  constructor(protected readonly name: string, private options: Options = {}) {}
+
+  describe(this: Server, verbose: boolean): string {
+    return verbose ? `${this.name} (${this.options.retries ?? 0} retries)` : this.name;
+  }
+}
+
+function request(url: string): string;
+function request(url: URL): string;
+function request(url: string | URL): string {
+  return `request ${url}`;
+}
+
+function createAgent() {
+  function request(url: string): string;
+  function request(url: string): string {
+    return `agent ${url}`;
+  }
+  return { request };
+}
+
+declare function unused(): void;
+
+module.exports = { Client, Server, request, createAgent };
@@ -0,0 +1,33 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2025 Datadog, Inc.
+ **/
+const { Client, Server, request, createAgent } = require('./instrumented.js');
+const { assert, getContext } = require('../common/preamble.js');
+const sendContext = getContext('orchestrion:undici:send');
+const clientContext = getContext('orchestrion:undici:client');
+const serverContext = getContext('orchestrion:undici:server');
+const describeContext = getContext('orchestrion:undici:describe');
+const requestContext = getContext('orchestrion:undici:request');
+
+(async () => {
+  const client = new Client('localhost', 8080);
+  assert.deepStrictEqual(clientContext, { start: true, end: true });
+  assert.strictEqual(await client.send('ping'), 'localhost:8080 ping');
+  assert.deepStrictEqual(sendContext, {
+    start: true,
+    end: true,
+    asyncStart: 'localhost:8080 ping',
+    asyncEnd: 'localhost:8080 ping',
+  });
+
+  const server = new Server('api', { retries: 2 });
+  assert.deepStrictEqual(serverContext, { start: true, end: true });
+  assert.strictEqual(server.describe(true), 'api (2 retries)');
+  assert.deepStrictEqual(describeContext, { start: true, end: true });
+
+  assert.strictEqual(request('https://example.com'), 'request https://example.com');
+  assert.deepStrictEqual(requestContext, {});
+  assert.strictEqual(createAgent().request('https://example.com'), 'agent https://example.com');
+  assert.deepStrictEqual(requestContext, { start: true, end: true });
+})();